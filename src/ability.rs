@@ -0,0 +1,134 @@
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+/// A special ability declared on an `EntityTemplate`
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub struct AbilityTemplate {
+    pub ability: Ability,
+    /// turns before the ability can be used again
+    #[serde(default)]
+    pub cooldown: usize,
+    /// when an active ability is used, by default whenever the monster sees an
+    /// enemy. Passive abilities ignore it
+    pub trigger: Option<AiCondition>,
+}
+
+impl AbilityTemplate {
+    /// Whether a monster in `situation` uses the ability: its trigger holds,
+    /// and using it would actually do something
+    pub fn ready(&self, situation: &AiSituation) -> bool {
+        situation.sees_enemy
+            && self
+                .trigger
+                .as_ref()
+                .is_none_or(|trigger| trigger.holds(situation))
+            && match self.ability {
+                Ability::Steal { .. } => situation.can_steal,
+                _ => true,
+            }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub enum Ability {
+    /// breathes at its enemy, hitting everybody in a cone
+    Breath { range: f32, damage: i32 },
+    /// calls monsters from this `EntityTemplate` to its side
+    Summon { template: String, count: usize },
+    /// takes a random item from the player next to it, then switches to the
+    /// named `BehaviourTemplate`, e.g. to run away
+    Steal { behaviour: String },
+    /// splits in two when hit, sharing the health it has left
+    Split,
+    /// heals by this fraction of the damage it deals
+    LifeDrain(f32),
+}
+impl Ability {
+    pub fn is_passive(&self) -> bool {
+        matches!(self, Ability::Split | Ability::LifeDrain(_))
+    }
+}
+
+/// How close a thief has to be to take something: orthogonally next to its victim
+const STEAL_REACH: f32 = 1.2;
+
+/// Whether a thief at `thief` can reach into the pockets of whoever is at `victim`
+pub fn within_steal_reach(thief: Point, victim: Point) -> bool {
+    thief.pythagorean_distance(victim) <= STEAL_REACH
+}
+
+/// An active ability and the turns left before it can be used
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbilityState {
+    pub template: AbilityTemplate,
+    pub cooldown: usize,
+}
+
+/// The tiles within `range` of `origin` at most `half_angle` degrees
+/// off the line towards `target`
+pub fn breath_cone(origin: Point, target: Point, range: f32, half_angle: f32) -> Vec<Point> {
+    let aim = target - origin;
+    let aim_angle = (aim.y as f32).atan2(aim.x as f32);
+    let reach = range.ceil() as i32;
+
+    (-reach..=reach)
+        .flat_map(|y| (-reach..=reach).map(move |x| Point::new(x, y)))
+        .filter(|offset| {
+            if *offset == Point::zero() || offset.pythagorean_distance(Point::zero()) > range {
+                return false;
+            }
+            let angle = (offset.y as f32).atan2(offset.x as f32) - aim_angle;
+            let off_line = angle.sin().atan2(angle.cos()).abs();
+            off_line <= half_angle.to_radians()
+        })
+        .map(|offset| origin + offset)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breath_cone() {
+        let origin = Point::new(10, 10);
+        let cone = breath_cone(origin, Point::new(15, 10), 4.0, 30.0);
+
+        assert!(cone.contains(&Point::new(11, 10)));
+        assert!(cone.contains(&Point::new(14, 10)));
+        assert!(cone.contains(&Point::new(13, 11)));
+        assert!(!cone.contains(&Point::new(15, 10)));
+        assert!(!cone.contains(&Point::new(10, 11)));
+        assert!(!cone.contains(&Point::new(9, 10)));
+        assert!(!cone.contains(&origin));
+    }
+
+    #[test]
+    fn test_steal_needs_orthogonal_victim() {
+        let thief = Point::new(5, 5);
+        assert!(within_steal_reach(thief, Point::new(6, 5)));
+        assert!(!within_steal_reach(thief, Point::new(6, 6)));
+
+        let steal = AbilityTemplate {
+            ability: Ability::Steal {
+                behaviour: "Getaway".to_string(),
+            },
+            cooldown: 0,
+            trigger: Some(AiCondition::EnemyWithin(1.5)),
+        };
+        // the diagonal player is within the trigger, but out of reach
+        let mut situation = AiSituation {
+            sees_enemy: true,
+            remembers_player: true,
+            health_fraction: 1.0,
+            enemy_distance: thief.pythagorean_distance(Point::new(6, 6)),
+            enemy_distance_from_post: 0.0,
+            can_steal: false,
+        };
+        assert!(!steal.ready(&situation));
+
+        situation.can_steal = true;
+        assert!(steal.ready(&situation));
+    }
+}
@@ -0,0 +1,196 @@
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+/// How rare a randomly rolled weapon is: how many affixes it gets, what its
+/// name is drawn in and how much more it is worth
+#[derive(Clone, Deserialize, Debug)]
+pub struct Rarity {
+    pub name: String,
+    pub weight: f32,
+    pub colour: (u8, u8, u8),
+    pub affixes: usize,
+    pub price_factor: f32,
+}
+
+/// A prefix ("Flaming") or suffix ("of Swiftness") changing a weapon
+#[derive(Clone, Deserialize, Debug)]
+pub struct Affix {
+    pub name: String,
+    pub weight: f32,
+    #[serde(default)]
+    pub damage: i32,
+    #[serde(default)]
+    pub stealth: i32,
+    #[serde(default)]
+    pub on_hit: Vec<StatusEffect>,
+}
+
+/// Something lingering on whoever was hit
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+pub enum StatusEffect {
+    /// takes `damage` at the end of every turn
+    Burning { damage: i32, turns: i32 },
+    /// deals `amount` less damage
+    Weakened { amount: i32, turns: i32 },
+}
+impl StatusEffect {
+    pub fn turns(&self) -> i32 {
+        match self {
+            StatusEffect::Burning { turns, .. } | StatusEffect::Weakened { turns, .. } => *turns,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusEffect::Burning { .. } => "burning",
+            StatusEffect::Weakened { .. } => "weakened",
+        }
+    }
+}
+
+impl Afflictions {
+    /// Runs every effect down by a turn, dropping the ones that wore off.
+    /// Returns who burned the afflicted this turn, and for how much
+    pub fn tick(&mut self) -> Vec<(Entity, i32)> {
+        let burns = self
+            .0
+            .iter_mut()
+            .filter_map(|affliction| {
+                affliction.turns_left -= 1;
+                match affliction.effect {
+                    StatusEffect::Burning { damage, .. } => Some((affliction.source, damage)),
+                    _ => None,
+                }
+            })
+            .collect();
+        self.0.retain(|a| a.turns_left > 0);
+        burns
+    }
+}
+
+/// The stealth `wielder` gets from their equipped weapons
+pub fn equipped_stealth(
+    weapons: &Query<(&Carried, &Weapon, &StealthBonus)>,
+    wielder: Entity,
+) -> i32 {
+    weapons
+        .iter()
+        .filter(|(carried, weapon, _)| carried.0 == wielder && weapon.equipped)
+        .map(|(_, _, bonus)| bonus.0)
+        .sum()
+}
+
+/// What rolling a weapon came up with
+#[derive(Clone, Debug, PartialEq)]
+pub struct RolledItem {
+    pub name: String,
+    pub rarity: usize,
+    pub prefix: Option<usize>,
+    pub suffix: Option<usize>,
+}
+
+/// Rolls a rarity for the item `name`, then that many affixes, at most
+/// one prefix and one suffix
+pub fn roll_affixes<R: Rng>(
+    rng: &mut R,
+    name: &str,
+    rarities: &[Rarity],
+    prefixes: &[Affix],
+    suffixes: &[Affix],
+) -> RolledItem {
+    let weights = rarities.iter().map(|r| r.weight).collect::<Vec<f32>>();
+    let rarity = rng.random_weighted_index(&weights).unwrap_or(0);
+
+    let (mut prefix, mut suffix) = (None, None);
+    let affixes = rarities.get(rarity).map_or(0, |r| r.affixes.min(2));
+    let pick = |rng: &mut R, affixes: &[Affix]| {
+        rng.random_weighted_index(&affixes.iter().map(|a| a.weight).collect::<Vec<f32>>())
+    };
+    if affixes == 2 {
+        prefix = pick(rng, prefixes);
+        suffix = pick(rng, suffixes);
+    } else if affixes == 1 {
+        if rng.gen_bool(0.5) {
+            prefix = pick(rng, prefixes);
+        } else {
+            suffix = pick(rng, suffixes);
+        }
+    }
+
+    let mut full_name = name.to_string();
+    if let Some(prefix) = prefix {
+        full_name = format!("{} {full_name}", prefixes[prefix].name);
+    }
+    if let Some(suffix) = suffix {
+        full_name = format!("{full_name} {}", suffixes[suffix].name);
+    }
+
+    RolledItem {
+        name: full_name,
+        rarity,
+        prefix,
+        suffix,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn affix(name: &str, weight: f32) -> Affix {
+        Affix {
+            name: name.to_string(),
+            weight,
+            damage: 1,
+            stealth: 0,
+            on_hit: Vec::new(),
+        }
+    }
+
+    fn rarity(affixes: usize) -> Rarity {
+        Rarity {
+            name: "Test".to_string(),
+            weight: 1.0,
+            colour: (255, 255, 255),
+            affixes,
+            price_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_roll_affixes() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let prefixes = [affix("Flaming", 1.0), affix("Cursed", 0.0)];
+        let suffixes = [affix("of Swiftness", 1.0)];
+
+        let rolled = roll_affixes(&mut rng, "Sword", &[rarity(0)], &prefixes, &suffixes);
+        assert_eq!(rolled.name, "Sword");
+
+        let rolled = roll_affixes(&mut rng, "Sword", &[rarity(2)], &prefixes, &suffixes);
+        assert_eq!(rolled.name, "Flaming Sword of Swiftness");
+        assert_eq!((rolled.prefix, rolled.suffix), (Some(0), Some(0)));
+
+        (0..10).for_each(|_| {
+            let rolled = roll_affixes(&mut rng, "Sword", &[rarity(1)], &prefixes, &suffixes);
+            assert!(rolled.prefix.is_some() != rolled.suffix.is_some());
+        });
+    }
+
+    #[test]
+    fn test_burn_goes_out() {
+        let source = Entity::from_raw(1);
+        let mut afflictions = Afflictions(vec![Affliction {
+            effect: StatusEffect::Burning {
+                damage: 2,
+                turns: 3,
+            },
+            source,
+            turns_left: 3,
+        }]);
+
+        (0..3).for_each(|_| assert_eq!(afflictions.tick(), vec![(source, 2)]));
+        assert!(afflictions.0.is_empty());
+        assert!(afflictions.tick().is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+use serde::Deserialize;
+
+/// A behaviour tree that `EntityTemplate`s refer to by name
+#[derive(Clone, Deserialize, Debug)]
+pub struct BehaviourTemplate {
+    pub name: String,
+    pub tree: AiNode,
+}
+
+/// A node of a behaviour tree. Every monster turn the tree is evaluated
+/// to pick the single action the monster takes
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub enum AiNode {
+    /// tries each child in turn, using the first one that acts
+    Selector(Vec<AiNode>),
+    /// only tries the child while the condition holds
+    If(AiCondition, Box<AiNode>),
+    /// runs at its enemy and attacks, as long as it can see them
+    Chase,
+    /// runs away from an enemy it can see
+    Flee,
+    /// stays about this far away from an enemy it can see
+    KeepDistance(f32),
+    /// stays put until an enemy comes this close, then chases
+    Ambush(f32),
+    /// chases enemies while they are this close to its post,
+    /// otherwise goes back to the post
+    Guard(f32),
+    /// goes to where the player was last seen and searches around
+    /// there for this many turns before forgetting about them
+    Investigate(usize),
+    /// walks between a few points of the map
+    Patrol,
+    /// wanders about at random
+    Wander,
+    /// walks from room to room
+    Roam,
+    /// stays within this distance of the player
+    Follow(f32),
+    Wait,
+}
+impl AiNode {
+    pub fn choose(&self, situation: &AiSituation) -> Option<AiAction> {
+        match self {
+            AiNode::Selector(children) => children.iter().find_map(|c| c.choose(situation)),
+            AiNode::If(condition, child) => {
+                if condition.holds(situation) {
+                    child.choose(situation)
+                } else {
+                    None
+                }
+            }
+            AiNode::Chase => situation.sees_enemy.then_some(AiAction::Chase),
+            AiNode::Flee => situation.sees_enemy.then_some(AiAction::Flee),
+            AiNode::KeepDistance(distance) => situation
+                .sees_enemy
+                .then_some(AiAction::KeepDistance(*distance)),
+            AiNode::Ambush(range) => {
+                if situation.sees_enemy && situation.enemy_distance <= *range {
+                    Some(AiAction::Chase)
+                } else {
+                    Some(AiAction::Wait)
+                }
+            }
+            AiNode::Guard(range) => {
+                if situation.sees_enemy && situation.enemy_distance_from_post <= *range {
+                    Some(AiAction::Chase)
+                } else {
+                    Some(AiAction::ReturnToPost)
+                }
+            }
+            AiNode::Investigate(search_turns) => situation
+                .remembers_player
+                .then_some(AiAction::Investigate(*search_turns)),
+            AiNode::Patrol => Some(AiAction::Patrol),
+            AiNode::Wander => Some(AiAction::Wander),
+            AiNode::Roam => Some(AiAction::Roam),
+            AiNode::Follow(distance) => Some(AiAction::Follow(*distance)),
+            AiNode::Wait => Some(AiAction::Wait),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub enum AiCondition {
+    SeesEnemy,
+    /// health is below this fraction of the maximum
+    HealthBelow(f32),
+    EnemyWithin(f32),
+    Not(Box<AiCondition>),
+}
+impl AiCondition {
+    pub fn holds(&self, situation: &AiSituation) -> bool {
+        match self {
+            AiCondition::SeesEnemy => situation.sees_enemy,
+            AiCondition::HealthBelow(fraction) => situation.health_fraction < *fraction,
+            AiCondition::EnemyWithin(distance) => situation.enemy_distance <= *distance,
+            AiCondition::Not(condition) => !condition.holds(situation),
+        }
+    }
+}
+
+/// What a monster knows when it decides what to do. Its enemy is the
+/// closest hostile it can see, often the player
+#[derive(Clone, Copy, Debug)]
+pub struct AiSituation {
+    pub sees_enemy: bool,
+    /// has seen the player recently and not yet given up looking for them
+    pub remembers_player: bool,
+    pub health_fraction: f32,
+    pub enemy_distance: f32,
+    pub enemy_distance_from_post: f32,
+    /// the enemy is the player, within a thief's reach and carrying something
+    pub can_steal: bool,
+}
+
+/// The actions a behaviour tree can choose, each carried out by its own system
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AiAction {
+    Chase,
+    Flee,
+    KeepDistance(f32),
+    ReturnToPost,
+    Investigate(usize),
+    Patrol,
+    Wander,
+    Roam,
+    Follow(f32),
+    Wait,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn situation(sees_enemy: bool, health_fraction: f32, enemy_distance: f32) -> AiSituation {
+        AiSituation {
+            sees_enemy,
+            remembers_player: false,
+            health_fraction,
+            enemy_distance,
+            enemy_distance_from_post: enemy_distance,
+            can_steal: false,
+        }
+    }
+
+    #[test]
+    fn test_selector_falls_through() {
+        let tree = AiNode::Selector(vec![
+            AiNode::If(AiCondition::HealthBelow(0.5), Box::new(AiNode::Flee)),
+            AiNode::Chase,
+            AiNode::Wander,
+        ]);
+
+        assert_eq!(
+            tree.choose(&situation(true, 1.0, 5.0)),
+            Some(AiAction::Chase)
+        );
+        assert_eq!(
+            tree.choose(&situation(true, 0.2, 5.0)),
+            Some(AiAction::Flee)
+        );
+        assert_eq!(
+            tree.choose(&situation(false, 0.2, 5.0)),
+            Some(AiAction::Wander)
+        );
+    }
+
+    #[test]
+    fn test_investigate_needs_memory() {
+        let tree = AiNode::Selector(vec![AiNode::Chase, AiNode::Investigate(4), AiNode::Wander]);
+
+        let mut lost_sight = situation(false, 1.0, 5.0);
+        assert_eq!(tree.choose(&lost_sight), Some(AiAction::Wander));
+        lost_sight.remembers_player = true;
+        assert_eq!(tree.choose(&lost_sight), Some(AiAction::Investigate(4)));
+    }
+
+    #[test]
+    fn test_ambush_and_guard() {
+        let ambush = AiNode::Ambush(3.0);
+        assert_eq!(
+            ambush.choose(&situation(true, 1.0, 5.0)),
+            Some(AiAction::Wait)
+        );
+        assert_eq!(
+            ambush.choose(&situation(true, 1.0, 2.0)),
+            Some(AiAction::Chase)
+        );
+
+        let guard = AiNode::Guard(6.0);
+        assert_eq!(
+            guard.choose(&situation(true, 1.0, 5.0)),
+            Some(AiAction::Chase)
+        );
+        assert_eq!(
+            guard.choose(&situation(true, 1.0, 8.0)),
+            Some(AiAction::ReturnToPost)
+        );
+    }
+}
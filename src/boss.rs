@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+/// One phase of a boss fight. A boss is in the last phase whose
+/// `health_below` is at least its health fraction
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub struct BossPhase {
+    pub health_below: f32,
+    /// the `BehaviourTemplate` the boss switches to, if any
+    pub behaviour: Option<String>,
+    /// used in turn, one every `cooldown` turns while the boss has an enemy
+    pub abilities: Vec<BossAbility>,
+    pub cooldown: usize,
+}
+impl BossPhase {
+    pub fn current(phases: &[BossPhase], health_fraction: f32) -> usize {
+        phases
+            .iter()
+            .rposition(|phase| health_fraction <= phase.health_below)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub enum BossAbility {
+    /// hits every enemy within `radius`
+    Slam {
+        radius: f32,
+        damage: i32,
+    },
+    /// spawns monsters from this `EntityTemplate` around the boss
+    SummonMinions {
+        template: String,
+        count: usize,
+    },
+    Heal(i32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(health_below: f32) -> BossPhase {
+        BossPhase {
+            health_below,
+            behaviour: None,
+            abilities: Vec::new(),
+            cooldown: 1,
+        }
+    }
+
+    #[test]
+    fn test_current_phase() {
+        let phases = vec![phase(1.0), phase(0.66), phase(0.33)];
+        assert_eq!(BossPhase::current(&phases, 1.0), 0);
+        assert_eq!(BossPhase::current(&phases, 0.7), 0);
+        assert_eq!(BossPhase::current(&phases, 0.5), 1);
+        assert_eq!(BossPhase::current(&phases, 0.1), 2);
+    }
+}
@@ -23,6 +23,15 @@ impl GameState for BTermState {
             let key_code: KeyCode = unsafe { std::mem::transmute(virtual_key_code as u32) };
             keyboard_input.press(key_code);
         }
+        if ctx.shift {
+            keyboard_input.press(KeyCode::LShift);
+        }
+
+        let mut mouse_input = self.app.world.resource_mut::<Input<MouseButton>>();
+        mouse_input.reset_all();
+        if ctx.left_click {
+            mouse_input.press(MouseButton::Left);
+        }
 
         self.app
             .insert_resource(Position(Point::from_tuple(ctx.mouse_pos())));
@@ -38,6 +47,7 @@ impl GameState for BTermState {
 fn bterm_runner(mut app: App) {
     let context = app
         .init_resource::<Input<KeyCode>>()
+        .init_resource::<Input<MouseButton>>()
         .world
         .remove_resource::<BTermResource>()
         .expect("BTerm context doesn't exist in the world, which is required in order to run");
@@ -37,8 +37,174 @@ pub struct WantsToAttack {
     pub target: Entity,
 }
 
+/// An attack that landed this turn, for the abilities reacting to it. The
+/// target's faction and position are kept in case the hit killed it
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Hit {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub amount: i32,
+    /// struck with weapons, rather than `Damages` such as burning
+    pub melee: bool,
+    pub target_faction: Option<String>,
+    pub target_position: Option<Point>,
+}
+
+/// Damage that doesn't depend on the attacker's weapons, e.g. a boss's slam
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Damages {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub amount: i32,
+}
+
+/// The behaviour tree a monster chooses its actions with
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Behaviour(pub AiNode);
+
+/// Where a monster started out, and goes back to when guarding
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Post(pub Point);
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Chasing;
+
 #[derive(Clone, Copy, Debug, PartialEq, Component)]
-pub struct ChasingPlayer;
+pub struct Fleeing;
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct KeepingDistance(pub f32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct ReturningToPost;
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Investigating {
+    pub search_turns: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Patrolling;
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Wandering;
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Roaming;
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Breathing {
+    pub range: f32,
+    pub damage: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Summoning {
+    pub template: String,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Stealing {
+    pub behaviour: String,
+}
+
+/// Every marker a behaviour tree's action or an ability can put on a monster
+pub type ActionMarkers = (
+    Chasing,
+    Fleeing,
+    KeepingDistance,
+    ReturningToPost,
+    Investigating,
+    Patrolling,
+    Wandering,
+    Roaming,
+    Following,
+    (Breathing, Summoning, Stealing),
+);
+
+/// The active abilities of a monster
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Abilities(pub Vec<AbilityState>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct SplitsOnHit;
+
+/// Heals by this fraction of the damage dealt
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct DrainsLife(pub f32);
+
+/// The `WanderMap` waypoint a roaming monster is heading for
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct RoamTarget(pub usize);
+
+/// Monsters run away from a player they can see once their health is below
+/// this fraction of the maximum, whatever their behaviour
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct FleesBelow(pub f32);
+
+/// How well a monster hears noises, as a multiple of their loudness
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Hearing(pub f32);
+
+/// A monster that fights in phases, using special abilities
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Boss {
+    pub phases: Vec<BossPhase>,
+    pub phase: usize,
+    /// turns until the next ability can be used
+    pub cooldown: usize,
+    pub next_ability: usize,
+}
+
+/// A monster fighting for the player, who follows them between levels
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Ally;
+
+/// Stays within this distance of the player
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Following(pub f32);
+
+/// The name of the `FactionTemplate` an entity belongs to
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Faction(pub String);
+
+/// The enemy a monster has chosen to act against this turn
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Target(pub Entity);
+
+/// A sleeping monster does nothing until a noise or the player wakes it
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Asleep;
+
+/// Added to a sleeping monster's rolls to notice the player
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Perception(pub i32);
+
+/// Makes the player harder to notice and quieter when moving
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Stealth(pub i32);
+
+/// A noise made this turn, heard by monsters within `loudness` steps
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Noise {
+    pub origin: Point,
+    pub loudness: f32,
+}
+
+/// Where a monster last saw or heard the player, and how long it has
+/// been searching for them there
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct LastSeenPlayer {
+    pub position: Point,
+    pub turns_searching: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct PatrolRoute {
+    pub waypoints: Vec<Point>,
+    pub next: usize,
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub struct Item;
@@ -70,9 +236,108 @@ pub struct ProvidesHealing {
 #[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub struct ProvidesDungeonMap;
 
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct ProvidesFood {
+    pub amount: i32,
+}
+
+/// Identifies everything the user is carrying
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct ProvidesIdentify;
+
+/// Turns the closest monster the user can see within `range` into an ally
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct ProvidesCharm {
+    pub range: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct ProvidesSummon {
+    pub template: String,
+    pub count: usize,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub struct Carried(pub Entity);
 
+/// A pile of gold coins
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Gold(pub i32);
+
+/// Heals one point of health every `every` turns
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Regeneration {
+    pub every: i32,
+    pub turns: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutoActionKind {
+    /// waits until healed
+    Rest,
+    /// walks towards the closest tile that hasn't been seen yet
+    Explore,
+    /// walks to the given point
+    Travel(Point),
+}
+
+/// Something the player keeps doing turn after turn until it's done,
+/// a key is pressed or something interrupts it
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct AutoAction {
+    pub kind: AutoActionKind,
+    /// the player's health last turn, to notice being hurt
+    pub health: Option<i32>,
+    pub position: Option<Point>,
+    /// the items and monsters already seen, so that only new ones interrupt
+    pub in_view: HashSet<Entity>,
+}
+impl AutoAction {
+    pub fn new(kind: AutoActionKind) -> Self {
+        Self {
+            kind,
+            health: None,
+            position: None,
+            in_view: HashSet::new(),
+        }
+    }
+}
+
+/// The turns the player can go before starving
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Hunger {
+    pub food: i32,
+    pub max: i32,
+}
+
+/// The gold the player has picked up
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Purse(pub i32);
+
+/// What an item costs in a shop
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Price(pub i32);
+
+/// A shopkeeper. What they have for sale is carried by them, already rolled
+/// and priced
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Shop;
+
+/// The shop whose screen is open, and whether the player is selling to it
+#[derive(Clone, Copy, Debug, PartialEq, Resource)]
+pub struct ShopScreen {
+    pub shop: Entity,
+    pub selling: bool,
+}
+
+/// What is left of a defeated monster
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Corpse;
+
+/// A monster summoned or split off mid-fight, which leaves nothing behind
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Summoned;
+
 #[derive(Clone, Copy, Debug, PartialEq, Component)]
 pub struct ActivateItem {
     pub used_by: Entity,
@@ -87,9 +352,52 @@ pub struct Weapon {
     pub equipped: bool,
 }
 
+/// The rarity of a weapon that rolled better than common
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct RarityName(pub String);
+
+/// Makes whoever has the weapon equipped harder to notice
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct StealthBonus(pub i32);
+
+/// What a weapon does to whoever it hits
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct OnHit(pub Vec<StatusEffect>);
+
+/// A status effect and who caused it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affliction {
+    pub effect: StatusEffect,
+    pub source: Entity,
+    pub turns_left: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Component)]
+pub struct Afflictions(pub Vec<Affliction>);
+
 #[derive(Clone, Debug, PartialEq, Resource)]
 pub struct MapInfo {
     pub name: String,
+    pub depth: usize,
 }
 #[derive(Clone, Debug, PartialEq, Component, Resource)]
 pub struct Position(pub Point);
+
+/// Replaces the `Position` of an entity left behind on a level
+/// the player isn't currently on
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Stashed {
+    pub map_level: usize,
+    pub position: Point,
+}
+
+/// Stairs leading from the level they're on to `destination`
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct Stairs {
+    pub destination: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+pub struct WantsToChangeLevel {
+    pub destination: usize,
+}
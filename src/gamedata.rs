@@ -1,7 +1,8 @@
 use crate::prelude::*;
+use bevy::ecs::system::EntityCommands;
 use ron::de::from_reader;
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::error::Error;
 use std::fs::File;
 
 #[derive(Clone, Deserialize, Debug, Resource)]
@@ -13,9 +14,12 @@ pub struct GameData {
 
     pub map_width: i32,
     pub map_height: i32,
-    pub num_monsters: usize,
     pub max_monster_visibility: f32,
     pub max_dijkstra_depth: f32,
+    pub noise: NoiseLevels,
+    pub hunger_states: Vec<HungerState>,
+    /// what the player loses every turn once they run out of food
+    pub starvation_damage: i32,
 
     pub tile_width: i32,
     pub tile_height: i32,
@@ -40,6 +44,21 @@ pub struct GameData {
 
     pub game_levels: Vec<GameLevel>,
 
+    pub spawn_tables: Vec<SpawnTable>,
+    pub loot_tables: Vec<LootTable>,
+    pub appearances: Vec<AppearanceGroup>,
+    pub rarities: Vec<Rarity>,
+    pub prefixes: Vec<Affix>,
+    pub suffixes: Vec<Affix>,
+    pub room_themes: Vec<RoomTheme>,
+
+    pub behaviours: Vec<BehaviourTemplate>,
+    /// the behaviour every ally gets, whatever it was before
+    pub ally_behaviour: String,
+    pub factions: Vec<FactionTemplate>,
+
+    pub map_validation: MapValidation,
+
     pub prefabs: Vec<Prefab>,
 }
 impl GameData {
@@ -59,34 +78,257 @@ impl GameData {
     pub fn load(path: &str) -> Self {
         let file = File::open(path).expect("Failed opening file");
 
-        from_reader(file).expect("unable to load templates")
+        let mut gamedata: Self = from_reader(file).expect("unable to load templates");
+        let (width, height) = (gamedata.map_width, gamedata.map_height);
+        gamedata.game_levels.iter_mut().for_each(|level| {
+            if let MapBuilderType::Fixed(builder) = &mut level.builder {
+                builder
+                    .load(width, height)
+                    .unwrap_or_else(|e| panic!("{e}"));
+            }
+        });
+        gamedata.validate();
+        gamedata
+    }
+
+    /// Panics on data that would only fail once it's used mid-game
+    fn validate(&self) {
+        self.prefabs.iter().for_each(|prefab| {
+            if prefab.min_count > prefab.max_count {
+                panic!(
+                    "Prefab {} has min_count {} above max_count {}",
+                    prefab.name, prefab.min_count, prefab.max_count
+                );
+            }
+        });
+
+        // every name referred to has to exist, or the lookups would panic mid-game
+        self.faction(&self.player_template.faction);
+        self.behaviour_named(&self.ally_behaviour);
+        self.player_template.companion.iter().for_each(|name| {
+            self.entity_template(name);
+        });
+        self.factions.iter().for_each(|faction| {
+            faction
+                .hostile_to
+                .iter()
+                .filter(|name| *name != "*")
+                .chain(faction.provoked.iter())
+                .for_each(|name| {
+                    self.faction(name);
+                });
+        });
+        self.entity_templates.iter().for_each(|template| {
+            template.faction.iter().for_each(|name| {
+                self.faction(name);
+            });
+            template.behaviour.iter().for_each(|name| {
+                self.behaviour_named(name);
+            });
+            template.loot.iter().for_each(|name| {
+                self.loot_table(name);
+            });
+            template.summons.iter().for_each(|name| {
+                self.entity_template(name);
+            });
+            template
+                .abilities
+                .iter()
+                .for_each(|ability| match &ability.ability {
+                    Ability::Summon { template, .. } => {
+                        self.entity_template(template);
+                    }
+                    Ability::Steal { behaviour } => {
+                        self.behaviour_named(behaviour);
+                    }
+                    _ => {}
+                });
+            if template
+                .phases
+                .as_ref()
+                .is_some_and(|phases| phases.is_empty())
+            {
+                panic!("Boss {} has no phases", template.name);
+            }
+            template.phases.iter().flatten().for_each(|phase| {
+                phase.behaviour.iter().for_each(|name| {
+                    self.behaviour_named(name);
+                });
+                phase.abilities.iter().for_each(|ability| {
+                    if let BossAbility::SummonMinions { template, .. } = ability {
+                        self.entity_template(template);
+                    }
+                });
+            });
+        });
+        self.loot_tables.iter().for_each(|table| {
+            table.items.iter().for_each(|entry| {
+                self.entity_template(&entry.name);
+            });
+        });
+        self.spawn_tables.iter().for_each(|table| {
+            table
+                .monsters
+                .iter()
+                .chain(table.items.iter())
+                .map(|entry| &entry.name)
+                .chain(table.guaranteed_monsters.iter())
+                .chain(table.guaranteed_items.iter())
+                .for_each(|name| {
+                    self.entity_template(name);
+                });
+        });
+        self.game_levels.iter().for_each(|level| {
+            level.boss.iter().for_each(|name| {
+                self.entity_template(name);
+            });
+            level.prefabs.iter().for_each(|name| {
+                self.prefab(name);
+            });
+            level.get_spawn_table(self);
+            level.exits.iter().for_each(|name| {
+                self.level_index(name);
+            });
+        });
+
+        // every unidentified item needs an appearance of its own
+        self.entity_templates
+            .iter()
+            .filter_map(|template| template.appearance.as_ref().map(|name| (template, name)))
+            .for_each(|(template, name)| {
+                if !self.appearances.iter().any(|group| group.name == *name) {
+                    panic!("No appearance group named {name} for {}", template.name);
+                }
+            });
+        self.appearances.iter().for_each(|group| {
+            let needed = self
+                .entity_templates
+                .iter()
+                .filter(|template| template.appearance.as_ref() == Some(&group.name))
+                .count();
+            if needed > group.descriptions.len() {
+                panic!(
+                    "Appearance group {} has {} descriptions for {needed} items",
+                    group.name,
+                    group.descriptions.len()
+                );
+            }
+        });
     }
 
     pub fn spawn_entities(
         &self,
         commands: &mut Commands,
-        rng: &mut ThreadRng,
-        level: usize,
-        spawn_points: &[Point],
+        rng: &mut StdRng,
+        level: &GameLevel,
+        map: &Map,
+        spawn_points: &[(Point, SpawnKind)],
+        limits: &SpawnLimits,
+    ) {
+        if let Some(table) = level.get_spawn_table(self) {
+            table
+                .populate(level.depth, map, spawn_points, limits, rng)
+                .iter()
+                .for_each(|(pt, name)| {
+                    self.spawn_entity(*pt, self.entity_template(name), commands, rng);
+                });
+        }
+    }
+
+    pub fn spawn_named_entities(
+        &self,
+        commands: &mut Commands,
+        rng: &mut StdRng,
+        named_spawns: &[(Point, String)],
     ) {
-        let mut available_entities = Vec::new();
+        named_spawns.iter().for_each(|(pt, name)| {
+            self.spawn_entity(*pt, self.entity_template(name), commands, rng);
+        });
+    }
+
+    pub fn entity_template(&self, name: &str) -> &EntityTemplate {
         self.entity_templates
             .iter()
-            .filter(|e| e.levels.contains(&level))
-            .for_each(|e| {
-                for _ in 0..e.frequency {
-                    available_entities.push(e)
-                }
-            });
+            .find(|e| e.name == name)
+            .unwrap_or_else(|| panic!("No entity template named {name}"))
+    }
 
-        spawn_points.iter().for_each(|pt| {
-            if let Some(template) = rng.random_slice_entry(&available_entities) {
-                self.spawn_entity(*pt, template, commands);
+    /// Drops what the monster `name` leaves behind when it dies at `pt`
+    pub fn drop_loot<R: Rng>(&self, commands: &mut Commands, rng: &mut R, name: &str, pt: Point) {
+        let template = self.entity_template(name);
+        if let Some(loot) = &template.loot {
+            let table = self.loot_table(loot);
+            let (items, gold) = table.roll(rng);
+            items.iter().for_each(|item| {
+                self.spawn_entity(pt, self.entity_template(item), commands, rng);
+            });
+            if gold > 0 {
+                let pile = self.spawn_entity(pt, self.entity_template("Gold"), commands, rng);
+                commands
+                    .entity(pile)
+                    .insert((Gold(gold), crate::components::Name(format!("{gold} Gold"))));
             }
-        });
+        }
+
+        if template.corpse {
+            commands.spawn((
+                Corpse,
+                Position(pt),
+                Render {
+                    color: ColorPair::new(DARK_RED, BLACK),
+                    glyph: '%',
+                },
+                crate::components::Name(format!("{} corpse", template.name)),
+            ));
+        }
+    }
+
+    pub fn spawn_monster(
+        &self,
+        commands: &mut Commands,
+        rng: &mut StdRng,
+        pt: Point,
+        name: &str,
+    ) -> Entity {
+        self.spawn_entity(pt, self.entity_template(name), commands, rng)
+    }
+
+    /// Spawns a monster called up mid-fight, which drops no loot
+    pub fn spawn_summoned(
+        &self,
+        commands: &mut Commands,
+        rng: &mut StdRng,
+        pt: Point,
+        name: &str,
+    ) -> Entity {
+        let entity = self.spawn_monster(commands, rng, pt, name);
+        commands.entity(entity).insert(Summoned);
+        entity
+    }
+
+    /// Spawns a monster that fights for the player
+    pub fn spawn_ally(&self, commands: &mut Commands, rng: &mut StdRng, pt: Point, name: &str) {
+        let entity = self.spawn_monster(commands, rng, pt, name);
+        self.make_ally(&mut commands.entity(entity));
+    }
+
+    /// Turns a monster into an ally, e.g. when charmed
+    pub fn make_ally(&self, entity: &mut EntityCommands) {
+        let behaviour = self.behaviour_named(&self.ally_behaviour);
+        entity.remove::<(Target, LastSeenPlayer, Asleep)>().insert((
+            Ally,
+            Faction(self.player_template.faction.clone()),
+            Behaviour(behaviour.tree.clone()),
+        ));
     }
 
-    fn spawn_entity(&self, pt: Point, template: &EntityTemplate, commands: &mut Commands) {
+    fn spawn_entity<R: Rng>(
+        &self,
+        pt: Point,
+        template: &EntityTemplate,
+        commands: &mut Commands,
+        rng: &mut R,
+    ) -> Entity {
         let mut entity = commands.spawn((
             Position(pt),
             Render {
@@ -103,7 +345,57 @@ impl GameData {
             EntityType::Enemy => {
                 entity.insert(Enemy {});
                 entity.insert(FieldOfView::new(template.fov.unwrap()));
-                entity.insert(ChasingPlayer {});
+                entity.insert(Behaviour(self.behaviour(template).tree.clone()));
+                entity.insert(Post(pt));
+                entity.insert(Faction(
+                    template
+                        .faction
+                        .clone()
+                        .unwrap_or_else(|| panic!("{} has no faction", template.name)),
+                ));
+                if let Some(fraction) = template.flee_below {
+                    entity.insert(FleesBelow(fraction));
+                }
+                if let Some(hearing) = template.hearing {
+                    entity.insert(Hearing(hearing));
+                }
+                entity.insert(Perception(template.perception.unwrap_or(0)));
+                if template.asleep {
+                    entity.insert(Asleep);
+                }
+                template
+                    .abilities
+                    .iter()
+                    .filter(|a| a.ability.is_passive())
+                    .for_each(|a| match a.ability {
+                        Ability::Split => {
+                            entity.insert(SplitsOnHit);
+                        }
+                        Ability::LifeDrain(fraction) => {
+                            entity.insert(DrainsLife(fraction));
+                        }
+                        _ => {}
+                    });
+                let active = template
+                    .abilities
+                    .iter()
+                    .filter(|a| !a.ability.is_passive())
+                    .map(|a| AbilityState {
+                        template: a.clone(),
+                        cooldown: 0,
+                    })
+                    .collect::<Vec<AbilityState>>();
+                if !active.is_empty() {
+                    entity.insert(Abilities(active));
+                }
+                if let Some(phases) = &template.phases {
+                    entity.insert(Boss {
+                        phases: phases.clone(),
+                        phase: 0,
+                        cooldown: phases[0].cooldown,
+                        next_ability: 0,
+                    });
+                }
                 entity.insert(Health {
                     current: template.hp.unwrap(),
                     max: template.hp.unwrap(),
@@ -121,16 +413,142 @@ impl GameData {
                     "MagicMap" => {
                         entity.insert(ProvidesDungeonMap);
                     }
+                    "Food" => {
+                        entity.insert(ProvidesFood { amount: *n });
+                    }
+                    "Identify" => {
+                        entity.insert(ProvidesIdentify);
+                    }
+                    "Charm" => {
+                        entity.insert(ProvidesCharm { range: *n });
+                    }
+                    "Gold" => {
+                        entity.insert(Gold(*n));
+                    }
+                    "Summon" => {
+                        entity.insert(ProvidesSummon {
+                            template: template.summons.clone().unwrap_or_else(|| {
+                                panic!("{} has nothing to summon", template.name)
+                            }),
+                            count: *n as usize,
+                        });
+                    }
                     _ => panic!("Don't know how to provide {provides}"),
                 })
         }
 
+        if let Some(price) = template.price {
+            entity.insert(Price(price));
+        }
+
         if let Some(damage) = &template.base_damage {
             entity.insert(Damage(*damage));
             if template.entity_type == EntityType::Item {
                 entity.insert(Weapon { equipped: false });
+                self.apply_affixes(&mut entity, rng, template, *damage);
             }
         }
+
+        let id = entity.id();
+        if let Some(count) = template.shop_stock {
+            commands.entity(id).insert(Shop);
+            self.stock_shop(commands, rng, id, count);
+        }
+        id
+    }
+
+    /// Rolls a rarity and affixes for a weapon, renaming and colouring it to match
+    fn apply_affixes<R: Rng>(
+        &self,
+        entity: &mut EntityCommands,
+        rng: &mut R,
+        template: &EntityTemplate,
+        damage: i32,
+    ) {
+        let rolled = roll_affixes(
+            rng,
+            &template.name,
+            &self.rarities,
+            &self.prefixes,
+            &self.suffixes,
+        );
+        let affixes = [
+            rolled.prefix.map(|i| &self.prefixes[i]),
+            rolled.suffix.map(|i| &self.suffixes[i]),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<&Affix>>();
+
+        entity.insert(Damage(
+            damage + affixes.iter().map(|a| a.damage).sum::<i32>(),
+        ));
+        let stealth = affixes.iter().map(|a| a.stealth).sum::<i32>();
+        if stealth != 0 {
+            entity.insert(StealthBonus(stealth));
+        }
+        let on_hit = affixes
+            .iter()
+            .flat_map(|a| a.on_hit.iter().copied())
+            .collect::<Vec<StatusEffect>>();
+        if !on_hit.is_empty() {
+            entity.insert(OnHit(on_hit));
+        }
+
+        if let Some(rarity) = self.rarities.get(rolled.rarity) {
+            let (r, g, b) = rarity.colour;
+            entity.insert((
+                crate::components::Name(rolled.name),
+                Render {
+                    color: ColorPair::new(RGB::from_u8(r, g, b), BLACK),
+                    glyph: template.glyph,
+                },
+            ));
+            if let Some(price) = template.price {
+                entity.insert(Price((price as f32 * rarity.price_factor).round() as i32));
+            }
+            if rarity.affixes > 0 {
+                entity.insert(RarityName(rarity.name.clone()));
+            }
+        }
+    }
+
+    /// Spawns the item `name` straight into `owner`'s inventory
+    fn spawn_carried<R: Rng>(
+        &self,
+        commands: &mut Commands,
+        rng: &mut R,
+        name: &str,
+        owner: Entity,
+    ) -> Entity {
+        let item = self.spawn_entity(Point::zero(), self.entity_template(name), commands, rng);
+        commands
+            .entity(item)
+            .remove::<Position>()
+            .insert(Carried(owner));
+        item
+    }
+
+    /// Gives `shopkeeper` `count` items with a price to sell, each rolled and
+    /// priced up front so that the player pays for exactly what they get
+    fn stock_shop<R: Rng>(
+        &self,
+        commands: &mut Commands,
+        rng: &mut R,
+        shopkeeper: Entity,
+        count: usize,
+    ) {
+        let priced = self
+            .entity_templates
+            .iter()
+            .filter(|t| t.price.is_some())
+            .map(|t| t.name.as_str())
+            .collect::<Vec<&str>>();
+        (0..count).for_each(|_| {
+            if let Some(name) = rng.random_slice_entry(&priced) {
+                self.spawn_carried(commands, rng, name, shopkeeper);
+            }
+        });
     }
 
     pub fn spawn_player(&self, commands: &mut Commands, pos: Point) {
@@ -147,6 +565,25 @@ impl GameData {
             },
             FieldOfView::new(self.player_template.fov),
             Damage(self.player_template.base_damage),
+            Stealth(self.player_template.stealth),
+            Faction(self.player_template.faction.clone()),
+            Purse(0),
+            Hunger {
+                food: self.player_template.food,
+                max: self.player_template.food,
+            },
+            Regeneration {
+                every: self.player_template.regeneration,
+                turns: 0,
+            },
+        ));
+    }
+
+    pub fn spawn_stairs(&self, commands: &mut Commands, pos: Point, destination: usize) {
+        commands.spawn((
+            Stairs { destination },
+            Position(pos),
+            crate::components::Name(format!("Stairs to {}", self.game_levels[destination].name)),
         ));
     }
 
@@ -163,32 +600,181 @@ impl GameData {
         ));
     }
 
-    pub fn apply_prefab(
+    /// Places every prefab the level allows, returning how many copies could not be placed
+    pub fn apply_prefabs(
         &self,
-        map: &mut Map,
-        rng: &mut ThreadRng,
-        player_start: Point,
-        amulet_start: Point,
-        entity_spawns: &mut Vec<Point>,
+        level: &GameLevel,
+        built_map: &mut BuiltMap,
+        rng: &mut StdRng,
         max_depth: f32,
-    ) {
-        self.prefabs[rng.random_slice_index(&self.prefabs).unwrap()].apply_prefab(
-            map,
+        history: &mut MapGenHistory,
+    ) -> usize {
+        let mut reserved = Vec::new();
+        level
+            .prefabs
+            .iter()
+            .map(|name| {
+                self.prefab(name)
+                    .apply_prefab(built_map, rng, &mut reserved, max_depth, history)
+            })
+            .sum()
+    }
+
+    pub fn behaviour_named(&self, name: &str) -> &BehaviourTemplate {
+        self.behaviours
+            .iter()
+            .find(|b| b.name == name)
+            .unwrap_or_else(|| panic!("No behaviour named {name}"))
+    }
+
+    pub fn behaviour(&self, template: &EntityTemplate) -> &BehaviourTemplate {
+        let name = template
+            .behaviour
+            .as_ref()
+            .unwrap_or_else(|| panic!("{} has no behaviour", template.name));
+        self.behaviour_named(name)
+    }
+
+    pub fn hunger_state(&self, hunger: &Hunger) -> &HungerState {
+        HungerState::current(&self.hunger_states, hunger.food, hunger.max)
+    }
+
+    pub fn faction(&self, name: &str) -> &FactionTemplate {
+        self.factions
+            .iter()
+            .find(|f| f.name == name)
+            .unwrap_or_else(|| panic!("No faction named {name}"))
+    }
+
+    /// Whether members of faction `from` attack members of faction `to`
+    pub fn is_hostile(&self, from: &str, to: &str) -> bool {
+        from != to
+            && self
+                .faction(from)
+                .hostile_to
+                .iter()
+                .any(|h| h == to || h == "*")
+    }
+
+    pub fn loot_table(&self, name: &str) -> &LootTable {
+        self.loot_tables
+            .iter()
+            .find(|t| t.name == name)
+            .unwrap_or_else(|| panic!("No loot table named {name}"))
+    }
+
+    pub fn spawn_table(&self, name: &str) -> &SpawnTable {
+        self.spawn_tables
+            .iter()
+            .find(|t| t.name == name)
+            .unwrap_or_else(|| panic!("No spawn table named {name}"))
+    }
+
+    pub fn prefab(&self, name: &str) -> &Prefab {
+        self.prefabs
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("No prefab named {name}"))
+    }
+
+    /// Finds the level a command line argument refers to: either a level number,
+    /// or a `MapBuilderType` written as RON (e.g. `Square`) which is wrapped in an
+    /// otherwise empty level. Defaults to the first level
+    pub fn find_level(
+        &self,
+        target: Option<&String>,
+    ) -> Result<GameLevel, Box<dyn Error + Send + Sync>> {
+        match target {
+            None => Ok(self.game_levels[0].clone()),
+            Some(target) => {
+                if let Ok(level) = target.parse::<usize>() {
+                    self.game_levels
+                        .get(level)
+                        .cloned()
+                        .ok_or_else(|| format!("No level {level}").into())
+                } else {
+                    Ok(GameLevel {
+                        name: target.clone(),
+                        builder: ron::from_str(target)?,
+                        theme: MapThemeType::Dungeon,
+                        branch: target.clone(),
+                        depth: 1,
+                        exits: Vec::new(),
+                        has_amulet: false,
+                        prefabs: Vec::new(),
+                        spawn_table: self.game_levels[0].spawn_table.clone(),
+                        boss: None,
+                        validation: None,
+                    })
+                }
+            }
+        }
+    }
+
+    pub fn level_index(&self, name: &str) -> usize {
+        self.game_levels
+            .iter()
+            .position(|l| l.name == name)
+            .unwrap_or_else(|| panic!("No level named {name}"))
+    }
+
+    pub fn build_level(
+        &self,
+        level: &GameLevel,
+        rng: &mut StdRng,
+        history: &mut MapGenHistory,
+    ) -> BuiltMap {
+        let mut built_map = level.get_builder(self).build(
             rng,
-            player_start,
-            amulet_start,
-            entity_spawns,
-            max_depth,
+            self.map_width,
+            self.map_height,
+            self.max_dijkstra_depth,
+            history,
         );
+
+        self.apply_prefabs(level, &mut built_map, rng, self.max_dijkstra_depth, history);
+
+        if let Some(table) = level.get_spawn_table(self) {
+            let (monsters, items) = table.spawn_counts(level.depth);
+            built_map.place_spawns(&self.room_themes, monsters, items, rng);
+        }
+
+        built_map
     }
 }
 
-#[derive(Clone, Copy, Deserialize, Debug)]
+#[derive(Clone, Deserialize, Debug)]
 pub struct PlayerTemplate {
     pub fov: i32,
     pub hp: i32,
     pub glyph: char,
     pub base_damage: i32,
+    pub stealth: i32,
+    pub faction: String,
+    /// how many turns the player can go without eating
+    pub food: i32,
+    /// the turns it takes the player to heal one point of health
+    pub regeneration: i32,
+    /// an ally the player starts the game with
+    pub companion: Option<String>,
+}
+
+/// Who a faction attacks. `"*"` stands for every other faction
+#[derive(Clone, Deserialize, Debug)]
+pub struct FactionTemplate {
+    pub name: String,
+    pub hostile_to: Vec<String>,
+    /// the faction members switch to when the player attacks one of them
+    pub provoked: Option<String>,
+}
+
+/// How many steps away each kind of action can be heard
+#[derive(Clone, Copy, Deserialize, Debug)]
+pub struct NoiseLevels {
+    /// the player moving, made quieter by their stealth
+    pub moving: f32,
+    pub fighting: f32,
+    pub using_items: f32,
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -200,14 +786,41 @@ pub struct AmuletTemplate {
 #[derive(Clone, Deserialize, Debug)]
 pub struct EntityTemplate {
     pub entity_type: EntityType,
-    pub levels: HashSet<usize>,
-    pub frequency: i32,
     pub name: String,
     pub glyph: char,
     pub provides: Option<Vec<(String, i32)>>,
     pub hp: Option<i32>,
     pub base_damage: Option<i32>,
     pub fov: Option<i32>,
+    /// the name of a `BehaviourTemplate`, needed by enemies
+    pub behaviour: Option<String>,
+    /// monsters without hearing are never woken by noise
+    pub hearing: Option<f32>,
+    /// the name of a `FactionTemplate`, needed by enemies
+    pub faction: Option<String>,
+    /// turns the monster into a boss
+    pub phases: Option<Vec<BossPhase>>,
+    #[serde(default)]
+    pub abilities: Vec<AbilityTemplate>,
+    /// the name of the `LootTable` the monster drops from
+    pub loot: Option<String>,
+    /// whether the monster leaves a corpse
+    #[serde(default)]
+    pub corpse: bool,
+    /// the `AppearanceGroup` an item is disguised by until it is identified
+    pub appearance: Option<String>,
+    /// the monster an item with `Summon` summons
+    pub summons: Option<String>,
+    /// the fraction of its health below which a monster flees
+    pub flee_below: Option<f32>,
+    /// how likely a sleeping monster is to notice the player
+    pub perception: Option<i32>,
+    #[serde(default)]
+    pub asleep: bool,
+    /// what an item costs in a shop; items without a price aren't sold
+    pub price: Option<i32>,
+    /// makes the monster a shopkeeper selling this many items
+    pub shop_stock: Option<usize>,
 }
 
 #[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
@@ -221,16 +834,41 @@ pub struct GameLevel {
     pub name: String,
     pub builder: MapBuilderType,
     pub theme: MapThemeType,
+    pub branch: String,
+    pub depth: usize,
+    /// names of the levels this level's down stairs lead to
+    #[serde(default)]
+    pub exits: Vec<String>,
+    #[serde(default)]
+    pub has_amulet: bool,
+    #[serde(default)]
+    pub prefabs: Vec<String>,
+    /// levels without a spawn table only get what their map places by name
+    pub spawn_table: Option<String>,
+    /// placed next to the amulet, or where the first exit would be
+    pub boss: Option<String>,
+    pub validation: Option<MapValidation>,
 }
 impl GameLevel {
-    pub fn get_builder<'b>(&self, gamedata: &'b GameData) -> &'b dyn MapBuilder {
-        match self.builder {
+    pub fn get_builder<'b>(&'b self, gamedata: &'b GameData) -> &'b dyn MapBuilder {
+        match &self.builder {
             MapBuilderType::CellularAutomata => &gamedata.automata_map_builder,
             MapBuilderType::Square => &gamedata.square_map_builder,
             MapBuilderType::DrunkardWalk => &gamedata.drunkard_map_builder,
+            MapBuilderType::Fixed(builder) => builder,
         }
     }
 
+    pub fn get_spawn_table<'b>(&self, gamedata: &'b GameData) -> Option<&'b SpawnTable> {
+        self.spawn_table
+            .as_ref()
+            .map(|name| gamedata.spawn_table(name))
+    }
+
+    pub fn get_validation<'b>(&'b self, gamedata: &'b GameData) -> &'b MapValidation {
+        self.validation.as_ref().unwrap_or(&gamedata.map_validation)
+    }
+
     pub fn get_theme<'b>(&self, gamedata: &'b GameData) -> &'b MapTheme {
         match self.theme {
             MapThemeType::Forest => &gamedata.forest_map_theme,
@@ -244,6 +882,7 @@ pub enum MapBuilderType {
     CellularAutomata,
     Square,
     DrunkardWalk,
+    Fixed(FixedMapBuilder),
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -251,3 +890,18 @@ pub enum MapThemeType {
     Forest,
     Dungeon,
 }
+
+/// Limits that every generated map of a level must stay within,
+/// checked by `mapgen-stats`
+#[derive(Clone, Deserialize, Debug)]
+pub struct MapValidation {
+    pub samples: usize,
+    pub min_floor_ratio: f32,
+    pub max_floor_ratio: f32,
+    pub min_path_length: f32,
+    pub max_dead_ends: usize,
+    pub max_spawn_density: f32,
+    pub max_prefab_failures: usize,
+    /// kept generous, so that only a builder gone badly wrong fails on a slow machine
+    pub max_generation_ms: f32,
+}
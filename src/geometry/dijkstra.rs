@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 use float_ord::FloatOrd;
-use std::{collections::VecDeque, f32::MAX};
+use std::collections::VecDeque;
 
 pub struct DijkstraMap {
     pub map: Vec<f32>,
@@ -15,7 +15,7 @@ impl DijkstraMap {
         let width = width.try_into().ok().unwrap();
         let height = height.try_into().ok().unwrap();
         let mut d = Self {
-            map: vec![MAX; width * height],
+            map: vec![f32::MAX; width * height],
         };
         d.build(starts, map, max_depth);
         d
@@ -39,10 +39,55 @@ impl DijkstraMap {
         map.get_available_exits(idx)
             .iter()
             .map(|(exit, _)| (*exit, self.map[*exit]))
-            .filter(|(_, distance)| *distance < MAX)
+            .filter(|(_, distance)| *distance < f32::MAX)
             .min_by_key(|(_, distance)| FloatOrd(*distance))
             .map(|(index, _)| index)
     }
+
+    /// A map for running away: the distances are multiplied by `factor`, which
+    /// should be negative, then rescanned so that the lowest exits lead away
+    /// from the start while still preferring escapes over dead ends
+    pub fn inverted(&self, factor: f32, map: &Map) -> Self {
+        let mut d = Self {
+            map: self
+                .map
+                .iter()
+                .map(|distance| {
+                    if *distance < f32::MAX {
+                        distance * factor
+                    } else {
+                        f32::MAX
+                    }
+                })
+                .collect(),
+        };
+
+        let mut queue = (0..d.map.len())
+            .filter(|idx| d.map[*idx] < f32::MAX)
+            .collect::<VecDeque<usize>>();
+        while let Some(index) = queue.pop_front() {
+            map.get_available_exits(index)
+                .iter()
+                .for_each(|(exit, cost)| {
+                    let distance = d.map[index] + cost;
+                    if distance < d.map[*exit] {
+                        d.map[*exit] = distance;
+                        queue.push_back(*exit);
+                    }
+                });
+        }
+        d
+    }
+
+    /// The exit leading furthest away, if any leads further than `idx`
+    pub fn find_highest_exit(&self, idx: usize, map: &Map) -> Option<usize> {
+        map.get_available_exits(idx)
+            .iter()
+            .map(|(exit, _)| (*exit, self.map[*exit]))
+            .filter(|(_, distance)| *distance < f32::MAX && *distance > self.map[idx])
+            .max_by_key(|(_, distance)| FloatOrd(*distance))
+            .map(|(index, _)| index)
+    }
 }
 
 struct QueueEntry(usize, f32);
@@ -64,3 +109,25 @@ impl PartialOrd for QueueEntry {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_inverted_leads_away() {
+        // a corridor with the player at x = 6
+        let mut map = Map::new(12, 3, TileType::Wall);
+        map.clear_rect(IRect::with_size(1, 1, 10, 1));
+        let player = map.point_to_index(Point::new(6, 1));
+        let chase = DijkstraMap::new(12, 3, &[player], &map, 100.0);
+        let flee = chase.inverted(-1.2, &map);
+
+        let step = |x| {
+            let exit = flee.find_lowest_exit(map.point_to_index(Point::new(x, 1)), &map);
+            exit.map(|idx| map.index_to_point(idx).x)
+        };
+        assert_eq!(step(4), Some(3));
+        assert_eq!(step(8), Some(9));
+    }
+}
@@ -1,6 +1,8 @@
 use std::ops::{Add, Div, Mul, Sub};
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
 pub struct Point {
     pub x: i32,
     pub y: i32,
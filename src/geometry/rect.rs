@@ -71,7 +71,7 @@ impl IRect {
         (offset.y * self.width() + offset.x) as usize
     }
 
-    pub fn points(&self) -> RectIter {
+    pub fn points(&self) -> RectIter<'_> {
         RectIter::new(self)
     }
 
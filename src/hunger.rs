@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+/// How hungry the player is. The player is in the first state whose
+/// `above` their food fraction is over, or the last one once nothing is left
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub struct HungerState {
+    pub name: String,
+    /// left out for the last state, which only comes once the food runs out
+    #[serde(default = "never")]
+    pub above: f32,
+    /// added to the damage the player deals in melee
+    #[serde(default)]
+    pub damage: i32,
+    pub colour: (u8, u8, u8),
+    /// whether the HUD shows the state at all
+    #[serde(default = "visible")]
+    pub visible: bool,
+}
+impl HungerState {
+    pub fn current(states: &[HungerState], food: i32, max: i32) -> &HungerState {
+        let fraction = food as f32 / max as f32;
+        states
+            .iter()
+            .find(|state| fraction > state.above)
+            .or(states.last())
+            .expect("There are no hunger states")
+    }
+}
+
+fn visible() -> bool {
+    true
+}
+
+fn never() -> f32 {
+    f32::INFINITY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(name: &str, above: f32) -> HungerState {
+        HungerState {
+            name: name.to_string(),
+            above,
+            damage: 0,
+            colour: (255, 255, 255),
+            visible: true,
+        }
+    }
+
+    #[test]
+    fn test_current_state() {
+        let states = vec![
+            state("Fed", 0.5),
+            state("Hungry", 0.0),
+            state("Starving", never()),
+        ];
+        assert_eq!(HungerState::current(&states, 100, 100).name, "Fed");
+        assert_eq!(HungerState::current(&states, 50, 100).name, "Hungry");
+        assert_eq!(HungerState::current(&states, 1, 100).name, "Hungry");
+        assert_eq!(HungerState::current(&states, 0, 100).name, "Starving");
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+/// The disguises a kind of item wears until it is identified, e.g. potions
+/// that are only known by their colour. `format` has a `{}` where the
+/// description goes
+#[derive(Clone, Deserialize, Debug)]
+pub struct AppearanceGroup {
+    pub name: String,
+    pub format: String,
+    pub descriptions: Vec<String>,
+}
+
+/// What every unidentified item looks like this run, and which items the
+/// player has identified. The appearances only depend on the seed, so the same
+/// run always gets the same ones
+#[derive(Clone, Debug, Default, Resource)]
+pub struct ItemKnowledge {
+    appearances: HashMap<String, String>,
+    known: HashSet<String>,
+}
+
+impl ItemKnowledge {
+    pub fn new(seed: u64, groups: &[AppearanceGroup], templates: &[EntityTemplate]) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut appearances = HashMap::new();
+
+        groups.iter().for_each(|group| {
+            let mut descriptions = group.descriptions.clone();
+            descriptions.shuffle(&mut rng);
+            let mut descriptions = descriptions.into_iter();
+
+            templates
+                .iter()
+                .filter(|t| t.appearance.as_ref() == Some(&group.name))
+                .for_each(|t| {
+                    let description = descriptions.next().unwrap_or_else(|| {
+                        panic!("Not enough {} appearances for {}", group.name, t.name)
+                    });
+                    appearances.insert(t.name.clone(), group.format.replace("{}", &description));
+                });
+        });
+
+        Self {
+            appearances,
+            known: HashSet::new(),
+        }
+    }
+
+    pub fn is_known(&self, name: &str) -> bool {
+        !self.appearances.contains_key(name) || self.known.contains(name)
+    }
+
+    /// Returns whether the item was unknown until now
+    pub fn identify(&mut self, name: &str) -> bool {
+        !self.is_known(name) && self.known.insert(name.to_string())
+    }
+
+    /// What the player calls the item `name`
+    pub fn display_name(&self, name: &str) -> String {
+        if self.is_known(name) {
+            name.to_string()
+        } else {
+            self.appearances[name].clone()
+        }
+    }
+
+    /// The identified items that have an appearance, with what they look like
+    pub fn known_items(&self) -> Vec<(&String, &String)> {
+        let mut known = self
+            .known
+            .iter()
+            .map(|name| (&self.appearances[name], name))
+            .collect::<Vec<(&String, &String)>>();
+        known.sort();
+        known
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_appearances() {
+        let gamedata = GameData::load(crate::GAME_DATA_PATH);
+        let knowledge = ItemKnowledge::new(7, &gamedata.appearances, &gamedata.entity_templates);
+        let again = ItemKnowledge::new(7, &gamedata.appearances, &gamedata.entity_templates);
+
+        let potions = ["Weak Healing Potion", "Healing Potion"];
+        assert_ne!(
+            knowledge.display_name(potions[0]),
+            knowledge.display_name(potions[1])
+        );
+        potions.iter().for_each(|potion| {
+            assert!(!knowledge.is_known(potion));
+            assert!(knowledge.display_name(potion).ends_with("potion"));
+            assert_eq!(knowledge.display_name(potion), again.display_name(potion));
+        });
+        assert_eq!(knowledge.display_name("Rusty Sword"), "Rusty Sword");
+
+        let mut knowledge = knowledge;
+        assert!(knowledge.identify("Healing Potion"));
+        assert!(!knowledge.identify("Healing Potion"));
+        assert!(!knowledge.identify("Rusty Sword"));
+        assert_eq!(knowledge.display_name("Healing Potion"), "Healing Potion");
+        assert_eq!(knowledge.known_items().len(), 1);
+    }
+}
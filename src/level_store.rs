@@ -0,0 +1,58 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::prelude::*;
+
+/// A level the player has left, kept so that it can be found
+/// exactly as it was. Its entities stay in the world as `Stashed`
+pub struct StoredLevel {
+    pub map: Map,
+    pub theme: MapTheme,
+    pub map_info: MapInfo,
+    pub wander_map: WanderMap,
+}
+
+#[derive(Default, Resource)]
+pub struct LevelStore {
+    pub levels: HashMap<usize, StoredLevel>,
+    /// the level the player most recently left
+    pub previous_level: Option<usize>,
+}
+
+/// Where the player ends up when arriving at `arrival`. Arriving on stairs
+/// would take them straight on to another level, so they step onto the
+/// nearest free floor tile instead
+pub fn step_off_stairs(map: &Map, arrival: Point, occupied: &mut HashSet<Point>) -> Point {
+    if map.tile_at(arrival) == TileType::Floor {
+        return arrival;
+    }
+    group_points(map, arrival, 1, occupied)
+        .first()
+        .copied()
+        .unwrap_or(arrival)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn test_step_off_stairs() {
+        let mut map = Map::new(5, 3, TileType::Wall);
+        map.clear_rect(IRect::with_size(1, 1, 3, 1));
+        map.set_tile(Point::new(1, 1), TileType::Exit);
+        map.set_tile(Point::new(2, 1), TileType::UpStairs);
+
+        let floor = Point::new(3, 1);
+        assert_eq!(step_off_stairs(&map, floor, &mut HashSet::new()), floor);
+
+        // the neighbouring exit is skipped for the floor behind it
+        let stairs = Point::new(2, 1);
+        assert_eq!(step_off_stairs(&map, stairs, &mut HashSet::new()), floor);
+
+        // nowhere free to go
+        let mut occupied = HashSet::from([floor]);
+        assert_eq!(step_off_stairs(&map, stairs, &mut occupied), stairs);
+    }
+}
@@ -0,0 +1,95 @@
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct LootEntry {
+    /// the `EntityTemplate` to drop
+    pub name: String,
+    pub weight: f32,
+}
+
+/// What a defeated monster drops where it died. Each roll has `drop_chance`
+/// of dropping one of the items, chosen by weight
+#[derive(Clone, Deserialize, Debug)]
+pub struct LootTable {
+    pub name: String,
+    #[serde(default = "one")]
+    pub rolls: usize,
+    pub drop_chance: f32,
+    #[serde(default)]
+    pub items: Vec<LootEntry>,
+    #[serde(default)]
+    pub gold_chance: f32,
+    /// the least and most gold dropped
+    #[serde(default)]
+    pub gold: (i32, i32),
+}
+
+fn one() -> usize {
+    1
+}
+
+impl LootTable {
+    /// The names of the items to drop and how much gold
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> (Vec<String>, i32) {
+        let weights = self.items.iter().map(|e| e.weight).collect::<Vec<f32>>();
+        let items = (0..self.rolls)
+            .filter_map(|_| {
+                if rng.gen::<f32>() < self.drop_chance {
+                    rng.random_weighted_index(&weights)
+                } else {
+                    None
+                }
+            })
+            .map(|idx| self.items[idx].name.clone())
+            .collect();
+
+        let (min, max) = self.gold;
+        let gold = if rng.gen::<f32>() < self.gold_chance {
+            rng.gen_range(min..=max.max(min))
+        } else {
+            0
+        };
+
+        (items, gold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn table(drop_chance: f32, gold_chance: f32) -> LootTable {
+        LootTable {
+            name: "Test".to_string(),
+            rolls: 3,
+            drop_chance,
+            items: vec![
+                LootEntry {
+                    name: "Healing Potion".to_string(),
+                    weight: 1.0,
+                },
+                LootEntry {
+                    name: "Rusty Sword".to_string(),
+                    weight: 0.0,
+                },
+            ],
+            gold_chance,
+            gold: (2, 4),
+        }
+    }
+
+    #[test]
+    fn test_roll() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let (items, gold) = table(1.0, 1.0).roll(&mut rng);
+        assert_eq!(items, vec!["Healing Potion"; 3]);
+        assert!((2..=4).contains(&gold));
+
+        let (items, gold) = table(0.0, 0.0).roll(&mut rng);
+        assert!(items.is_empty());
+        assert_eq!(gold, 0);
+    }
+}
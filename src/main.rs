@@ -1,34 +1,58 @@
 #![allow(clippy::type_complexity)] // queries create complex types
+#![allow(clippy::too_many_arguments)] // systems need many parameters
 
+mod ability;
+mod affix;
+mod behaviour;
+mod boss;
 mod bterm_plugin;
 mod camera;
 mod components;
 mod gamedata;
 mod geometry;
+mod hunger;
+mod identification;
+mod level_store;
+mod loot;
 mod map;
 mod map_builder;
+mod map_export;
+mod mapgen_stats;
+mod mapgen_viewer;
 mod random;
+mod spawn_table;
 mod systems;
 mod turn_state;
+mod wander_map;
 
 const GAME_DATA_PATH: &str = "resources/gamedata.ron";
 
 mod prelude {
-    pub use crate::camera::*;
+    pub use crate::ability::*;
+    pub use crate::affix::*;
+    pub use crate::behaviour::*;
+    pub use crate::boss::*;
     pub use crate::components::*;
     pub use crate::gamedata::*;
     pub use crate::geometry::dijkstra::DijkstraMap;
     pub use crate::geometry::fov::field_of_view_set;
     pub use crate::geometry::prelude::*;
+    pub use crate::hunger::*;
+    pub use crate::identification::*;
+    pub use crate::level_store::*;
+    pub use crate::loot::*;
     pub use crate::map::*;
     pub use crate::map_builder::prelude::*;
     pub use crate::random::*;
+    pub use crate::spawn_table::*;
     pub use crate::systems::*;
     pub use crate::turn_state::*;
+    pub use crate::wander_map::*;
 
     pub use bevy::prelude::*;
-    pub use rand::rngs::ThreadRng;
+    pub use rand::rngs::StdRng;
     pub use rand::Rng;
+    pub use rand::SeedableRng;
 
     pub use bracket_lib::color::*;
     pub use bracket_lib::terminal::main_loop;
@@ -56,6 +80,12 @@ fn main() -> BError {
 
     if args.len() > 1 && args[1] == "test" {
         test_harness(gamedata)
+    } else if args.len() > 1 && args[1] == "mapgen-viewer" {
+        mapgen_viewer::mapgen_viewer(gamedata, args.get(2), args.get(3))
+    } else if args.len() > 1 && args[1] == "mapgen-stats" {
+        mapgen_stats::mapgen_stats(gamedata, args.get(2), args.get(3))
+    } else if args.len() > 1 && args[1] == "map-export" {
+        map_export::map_export(gamedata, args.get(2), args.get(3), args.get(4))
     } else {
         let context = BTermBuilder::new()
             .with_title(&gamedata.title)
@@ -107,34 +137,32 @@ fn main() -> BError {
 }
 
 fn test_harness(gamedata: GameData) -> BError {
-    let mut rng = rand::thread_rng();
-    let BuiltMap {
-        mut map,
-        mut entity_spawns,
-        player_start,
-        amulet_start,
-    } = gamedata.drunkard_map_builder.build(
+    let mut rng = StdRng::from_entropy();
+    let mut history = MapGenHistory::default();
+    let mut built_map = gamedata.drunkard_map_builder.build(
         &mut rng,
         gamedata.map_width,
         gamedata.map_height,
-        gamedata.num_monsters,
         gamedata.max_dijkstra_depth,
+        &mut history,
     );
 
-    gamedata.apply_prefab(
-        &mut map,
+    gamedata.prefab("Fortress").apply_prefab(
+        &mut built_map,
         &mut rng,
-        player_start,
-        amulet_start,
-        &mut entity_spawns,
+        &mut Vec::new(),
         gamedata.max_dijkstra_depth,
+        &mut history,
     );
+    let (monsters, items) = gamedata.spawn_table("Main").spawn_counts(1);
+    built_map.place_spawns(&gamedata.room_themes, monsters, items, &mut rng);
+
     display(
         "Final Map",
-        &map,
-        &player_start,
-        &amulet_start,
-        &entity_spawns,
+        &built_map.map,
+        &built_map.player_start,
+        &built_map.amulet_start,
+        &built_map.entity_spawns,
         &gamedata.dungeon_map_theme,
     )
 }
@@ -144,7 +172,19 @@ pub fn display(
     map: &Map,
     player_start: &Point,
     amulet_start: &Point,
-    entity_spawns: &[Point],
+    entity_spawns: &[(Point, SpawnKind)],
+    theme: &MapTheme,
+) -> BError {
+    let mut markers = vec![(*player_start, '@'), (*amulet_start, 'A')];
+    markers.extend(entity_spawns.iter().map(|(p, _)| (*p, 'M')));
+
+    display_with_markers(title, map, &markers, theme)
+}
+
+pub fn display_with_markers(
+    title: &str,
+    map: &Map,
+    markers: &[(Point, char)],
     theme: &MapTheme,
 ) -> BError {
     use colored::*;
@@ -155,10 +195,8 @@ pub fn display(
         output[idx] = theme.tile_to_render(*t, Revealed::Seen);
     });
 
-    output[map.point_to_index(*player_start)] = '@';
-    output[map.point_to_index(*amulet_start)] = 'A';
-    entity_spawns.iter().for_each(|p| {
-        output[map.point_to_index(*p)] = 'M';
+    markers.iter().for_each(|(p, marker)| {
+        output[map.point_to_index(*p)] = *marker;
     });
 
     print!("\x1B[2J"); // CLS!
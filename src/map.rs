@@ -1,20 +1,22 @@
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
 };
 
 use float_ord::FloatOrd;
+use serde::{Deserialize, Serialize};
 
 use crate::prelude::*;
 
 pub const UNREACHABLE: f32 = f32::MAX;
 pub const CARDINALS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Deserialize, Serialize)]
 pub enum TileType {
     Wall,
     Floor,
     Exit,
+    UpStairs,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -24,7 +26,7 @@ pub enum Revealed {
     Seen,
 }
 
-#[derive(Resource)]
+#[derive(Clone, Resource)]
 pub struct Map {
     pub tiles: Vec<TileType>,
     world_rect: IRect,
@@ -57,7 +59,9 @@ impl Map {
 
     pub fn can_enter_tile(&self, point: Point) -> bool {
         self.in_bounds(point)
-            && (self.tile_at(point) == TileType::Floor || self.tile_at(point) == TileType::Exit)
+            && (self.tile_at(point) == TileType::Floor
+                || self.tile_at(point) == TileType::Exit
+                || self.tile_at(point) == TileType::UpStairs)
     }
 
     pub fn tile_at(&self, point: Point) -> TileType {
@@ -133,6 +137,7 @@ impl Map {
                         // so things are not encompassed
                         TileType::Floor => return,
                         TileType::Exit => return,
+                        TileType::UpStairs => return,
                     }
                 }
             }
@@ -171,6 +176,47 @@ impl Map {
             .map(|(idx, _)| self.index_to_point(idx))
             .unwrap_or(point)
     }
+    /// Picks a random floor tile in the far half of the map as measured from `point`,
+    /// keeping clear of everything in `avoid` and never on a tile in `taken`
+    pub fn random_distant_floor(
+        &mut self,
+        point: Point,
+        avoid: &[Point],
+        taken: &HashSet<Point>,
+        rng: &mut StdRng,
+        max_depth: f32,
+    ) -> Point {
+        let djikstra_map = self.dijkstra_map(point, max_depth);
+        let furthest = djikstra_map
+            .map
+            .iter()
+            .filter(|dist| **dist < UNREACHABLE)
+            .max_by_key(|dist| FloatOrd(**dist))
+            .cloned()
+            .unwrap_or(0.0);
+
+        let candidates = djikstra_map
+            .map
+            .iter()
+            .enumerate()
+            .filter(|(idx, dist)| {
+                let candidate = self.index_to_point(*idx);
+                self.tiles[*idx] == TileType::Floor
+                    && !taken.contains(&candidate)
+                    && **dist < UNREACHABLE
+                    && **dist >= furthest / 2.0
+                    && avoid
+                        .iter()
+                        .all(|pt| pt.pythagorean_distance(candidate) > 10.0)
+            })
+            .map(|(idx, _)| self.index_to_point(idx))
+            .collect::<Vec<Point>>();
+
+        rng.random_slice_entry(&candidates)
+            .cloned()
+            .unwrap_or_else(|| self.find_most_distant(point, max_depth))
+    }
+
     pub fn dijkstra_map(&mut self, point: Point, max_depth: f32) -> Arc<DijkstraMap> {
         self.cached_dijkstra_map
             .iter()
@@ -179,14 +225,14 @@ impl Map {
             })
             .map(|(_, _, cached_map)| cached_map.clone())
             .next()
-            .unwrap_or({
+            .unwrap_or_else(|| {
                 let new_map = Arc::new(self.uncached_dijkstra_map(point, max_depth));
                 self.cached_dijkstra_map = Some((point, max_depth, new_map.clone()));
                 new_map
             })
     }
 
-    pub fn uncached_dijkstra_map(&mut self, point: Point, max_depth: f32) -> DijkstraMap {
+    pub fn uncached_dijkstra_map(&self, point: Point, max_depth: f32) -> DijkstraMap {
         DijkstraMap::new(
             self.world_rect.width(),
             self.world_rect.height(),
@@ -215,7 +261,13 @@ impl Map {
         IRect::with_size(1, 1, self.width() - 2, self.height() - 2)
     }
 
-    pub fn connect_disconnected(&mut self, player_pos: Point, rng: &mut ThreadRng, max_depth: f32) {
+    pub fn connect_disconnected(
+        &mut self,
+        player_pos: Point,
+        rng: &mut StdRng,
+        max_depth: f32,
+        history: &mut MapGenHistory,
+    ) {
         let walled_rect = self.walled_rect();
         'outer: loop {
             // no point in using the cached dijkstra map because we'll be changing the map
@@ -253,6 +305,7 @@ impl Map {
                             target = self.point_to_index(new_target_point);
                         }
                     }
+                    history.record("Connect disconnected area", self);
                 }
                 None => {
                     break 'outer;
@@ -282,6 +335,20 @@ impl Map {
             TileType::Wall => true,
             TileType::Floor => false,
             TileType::Exit => false,
+            TileType::UpStairs => false,
         }
     }
 }
+
+/// Dijkstra maps towards targets that may move, so each is only built once
+/// per system run however many monsters are heading for it
+#[derive(Default)]
+pub struct TargetMaps(HashMap<Point, Arc<DijkstraMap>>);
+impl TargetMaps {
+    pub fn get(&mut self, map: &mut Map, target: Point, max_depth: f32) -> Arc<DijkstraMap> {
+        self.0
+            .entry(target)
+            .or_insert_with(|| map.dijkstra_map(target, max_depth))
+            .clone()
+    }
+}
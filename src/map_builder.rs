@@ -1,7 +1,8 @@
 pub mod automata;
 pub mod drunkard;
-pub mod empty;
+pub mod fixed;
 pub mod prefab;
+pub mod rooms;
 pub mod square;
 
 use crate::prelude::*;
@@ -10,8 +11,9 @@ use serde::Deserialize;
 pub mod prelude {
     pub use crate::map_builder::automata::*;
     pub use crate::map_builder::drunkard::*;
-    pub use crate::map_builder::empty::*;
+    pub use crate::map_builder::fixed::*;
     pub use crate::map_builder::prefab::*;
+    pub use crate::map_builder::rooms::*;
     pub use crate::map_builder::square::*;
     pub use crate::map_builder::*;
 }
@@ -19,22 +21,57 @@ pub mod prelude {
 pub trait MapBuilder {
     fn build(
         &self,
-        rng: &mut ThreadRng,
+        rng: &mut StdRng,
         width: i32,
         height: i32,
-        num_monsters: usize,
         max_depth: f32,
+        history: &mut MapGenHistory,
     ) -> BuiltMap;
 }
 
+/// Copies of the map taken after each step of building it, so that
+/// the steps can be replayed by the mapgen viewer. No snapshots are kept
+/// unless the history was created with `recording`, but prefab failures
+/// are always counted
+#[derive(Default)]
+pub struct MapGenHistory {
+    recording: bool,
+    pub snapshots: Vec<MapSnapshot>,
+    pub prefab_failures: usize,
+}
+impl MapGenHistory {
+    pub fn recording() -> Self {
+        Self {
+            recording: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn record(&mut self, label: impl Into<String>, map: &Map) {
+        if self.recording {
+            self.snapshots.push(MapSnapshot {
+                label: label.into(),
+                tiles: map.tiles.clone(),
+            });
+        }
+    }
+}
+
+pub struct MapSnapshot {
+    pub label: String,
+    pub tiles: Vec<TileType>,
+}
+
 #[derive(Copy, Clone, Deserialize, Debug, Resource)]
 pub struct MapTheme {
     seen_wall: char,
     seen_floor: char,
     seen_exit: char,
+    seen_up_stairs: char,
     mapped_wall: char,
     mapped_floor: char,
     mapped_exit: char,
+    mapped_up_stairs: char,
     not_seen: char,
 }
 
@@ -44,9 +81,11 @@ impl MapTheme {
             (TileType::Wall, Revealed::Seen) => self.seen_wall,
             (TileType::Floor, Revealed::Seen) => self.seen_floor,
             (TileType::Exit, Revealed::Seen) => self.seen_exit,
+            (TileType::UpStairs, Revealed::Seen) => self.seen_up_stairs,
             (TileType::Wall, Revealed::Mapped) => self.mapped_wall,
             (TileType::Floor, Revealed::Mapped) => self.mapped_floor,
             (TileType::Exit, Revealed::Mapped) => self.mapped_exit,
+            (TileType::UpStairs, Revealed::Mapped) => self.mapped_up_stairs,
             (_, Revealed::NotSeen) => self.not_seen,
         }
     }
@@ -54,36 +93,36 @@ impl MapTheme {
 
 pub struct BuiltMap {
     pub map: Map,
-    pub entity_spawns: Vec<Point>,
+    pub entity_spawns: Vec<(Point, SpawnKind)>,
+    pub named_spawns: Vec<(Point, String)>,
+    pub rooms: Vec<Room>,
+    /// where spawns may spread out to, filled in by `place_spawns`
+    pub spawn_limits: SpawnLimits,
     pub player_start: Point,
     pub amulet_start: Point,
 }
-
-pub fn determine_entity_spawn_points(
-    map: &Map,
-    player_pos: Point,
-    rng: &mut ThreadRng,
-    num_monsters: usize,
-) -> Vec<Point> {
-    let mut spawnable_tiles = map
-        .tiles
-        .iter()
-        .enumerate()
-        .filter(|(idx, tile)| {
-            **tile == TileType::Floor
-                && player_pos.pythagorean_distance(map.index_to_point(*idx)) > 10.0
-        })
-        .map(|(idx, _)| map.index_to_point(idx))
-        .collect::<Vec<Point>>();
-
-    let mut spawns = Vec::new();
-    for _ in 0..num_monsters {
-        rng.random_slice_index(&spawnable_tiles)
-            .iter()
-            .for_each(|target_index| {
-                spawns.push(spawnable_tiles[*target_index]);
-                spawnable_tiles.remove(*target_index);
-            });
+impl BuiltMap {
+    pub fn apply_glyph(&mut self, pt: Point, glyph: &PrefabGlyph) {
+        match glyph {
+            PrefabGlyph::Tile(tile) => {
+                self.map.set_tile(pt, *tile);
+            }
+            PrefabGlyph::Spawn => {
+                self.map.set_tile(pt, TileType::Floor);
+                self.entity_spawns.push((pt, SpawnKind::Any));
+            }
+            PrefabGlyph::Entity(name) => {
+                self.map.set_tile(pt, TileType::Floor);
+                self.named_spawns.push((pt, name.clone()));
+            }
+            PrefabGlyph::PlayerStart => {
+                self.map.set_tile(pt, TileType::Floor);
+                self.player_start = pt;
+            }
+            PrefabGlyph::Exit => {
+                self.map.set_tile(pt, TileType::Floor);
+                self.amulet_start = pt;
+            }
+        }
     }
-    spawns
 }
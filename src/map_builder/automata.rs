@@ -4,30 +4,36 @@ use serde::Deserialize;
 #[derive(Copy, Clone, Deserialize, Debug)]
 pub struct CellularAutomataMapBuilder {
     pub num_iterations: i32,
+    pub num_regions: usize,
 }
 impl MapBuilder for CellularAutomataMapBuilder {
     fn build(
         &self,
-        rng: &mut ThreadRng,
+        rng: &mut StdRng,
         width: i32,
         height: i32,
-        num_monsters: usize,
         max_depth: f32,
+        history: &mut MapGenHistory,
     ) -> BuiltMap {
         let mut map = Self::random_noise_map(width, height, rng);
+        history.record("Random noise", &map);
 
-        for _ in 0..self.num_iterations {
+        for i in 0..self.num_iterations {
             Self::iteration(&mut map);
+            history.record(format!("Cellular automata iteration {}", i + 1), &map);
         }
         let player_start = map.closest_floor_point(map.center());
 
-        map.connect_disconnected(player_start, rng, max_depth);
+        map.connect_disconnected(player_start, rng, max_depth, history);
 
         let amulet_start = map.find_most_distant(player_start, max_depth);
-        let entity_spawns = determine_entity_spawn_points(&map, player_start, rng, num_monsters);
+        let rooms = voronoi_rooms(&map, rng, self.num_regions);
         BuiltMap {
             map,
-            entity_spawns,
+            entity_spawns: Vec::new(),
+            named_spawns: Vec::new(),
+            rooms,
+            spawn_limits: SpawnLimits::default(),
             player_start,
             amulet_start,
         }
@@ -35,7 +41,7 @@ impl MapBuilder for CellularAutomataMapBuilder {
 }
 
 impl CellularAutomataMapBuilder {
-    fn random_noise_map(width: i32, height: i32, rng: &mut ThreadRng) -> Map {
+    fn random_noise_map(width: i32, height: i32, rng: &mut StdRng) -> Map {
         let mut map = Map::new(width, height, TileType::Wall);
         for y in 1..height - 1 {
             for x in 1..width - 1 {
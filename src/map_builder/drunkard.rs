@@ -5,35 +5,42 @@ use serde::Deserialize;
 pub struct DrunkardWalkMapBuilder {
     pub cleared_ratio: f32,
     pub stagger_distance: usize,
+    pub num_regions: usize,
 }
 
 impl MapBuilder for DrunkardWalkMapBuilder {
     fn build(
         &self,
-        rng: &mut ThreadRng,
+        rng: &mut StdRng,
         width: i32,
         height: i32,
-        num_monsters: usize,
         max_depth: f32,
+        history: &mut MapGenHistory,
     ) -> BuiltMap {
         let mut map = Map::new(width, height, TileType::Wall);
         let desired_cleared = ((map.width() * map.height()) as f32 * self.cleared_ratio) as i32;
         let mut cleared = 0;
+        let mut drunkards = 0;
 
         while cleared < desired_cleared {
             let start = Point::new(rng.gen_range(1..width - 1), rng.gen_range(1..height - 1));
             cleared += self.drunkard(&mut map, start, rng);
+            drunkards += 1;
+            history.record(format!("Drunkard {drunkards}"), &map);
         }
 
         let player_start = map.closest_floor_point(map.center());
 
-        map.connect_disconnected(player_start, rng, max_depth);
+        map.connect_disconnected(player_start, rng, max_depth, history);
 
         let amulet_start = map.find_most_distant(player_start, max_depth);
-        let entity_spawns = determine_entity_spawn_points(&map, player_start, rng, num_monsters);
+        let rooms = voronoi_rooms(&map, rng, self.num_regions);
         BuiltMap {
             map,
-            entity_spawns,
+            entity_spawns: Vec::new(),
+            named_spawns: Vec::new(),
+            rooms,
+            spawn_limits: SpawnLimits::default(),
             player_start,
             amulet_start,
         }
@@ -41,7 +48,7 @@ impl MapBuilder for DrunkardWalkMapBuilder {
 }
 
 impl DrunkardWalkMapBuilder {
-    fn drunkard(&self, map: &mut Map, start: Point, rng: &mut ThreadRng) -> i32 {
+    fn drunkard(&self, map: &mut Map, start: Point, rng: &mut StdRng) -> i32 {
         let walled_rect = map.walled_rect();
         let mut drunkard_pos = start;
         let mut cleared = 0;
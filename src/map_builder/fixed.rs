@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+
+use crate::prelude::*;
+use serde::Deserialize;
+
+/// Builds a hand-drawn map from a text file. The file uses the same legend
+/// as prefabs, so only the spawns drawn into it are used
+#[derive(Clone, Deserialize, Debug)]
+pub struct FixedMapBuilder {
+    pub file: String,
+    #[serde(default)]
+    pub legend: HashMap<char, PrefabGlyph>,
+    /// the file's contents, read by `load` when the game data loads
+    #[serde(skip)]
+    pub text: Vec<String>,
+}
+impl MapBuilder for FixedMapBuilder {
+    fn build(
+        &self,
+        _rng: &mut StdRng,
+        width: i32,
+        height: i32,
+        max_depth: f32,
+        history: &mut MapGenHistory,
+    ) -> BuiltMap {
+        let text = &self.text;
+        let map = Map::new(width, height, TileType::Wall);
+        let center = map.center();
+        let mut built_map = BuiltMap {
+            map,
+            entity_spawns: Vec::new(),
+            named_spawns: Vec::new(),
+            rooms: Vec::new(),
+            spawn_limits: SpawnLimits::default(),
+            player_start: center,
+            amulet_start: center,
+        };
+
+        let text_width = text.iter().map(|s| s.chars().count()).max().unwrap_or(0) as i32;
+        let text_height = text.len() as i32;
+        let offset = Point::new((width - text_width) / 2, (height - text_height) / 2);
+
+        let mut has_player_start = false;
+        let mut has_exit = false;
+        for (ty, row) in text.iter().enumerate() {
+            for (tx, c) in row.chars().enumerate() {
+                let pt = offset + Point::new(tx as i32, ty as i32);
+                let glyph = self.glyph(c);
+                has_player_start |= glyph == PrefabGlyph::PlayerStart;
+                has_exit |= glyph == PrefabGlyph::Exit;
+                built_map.apply_glyph(pt, &glyph);
+            }
+        }
+
+        history.record(format!("Map file {}", self.file), &built_map.map);
+        built_map.rooms = Room::whole_map(&built_map.map);
+
+        if !has_player_start {
+            built_map.player_start = built_map.map.closest_floor_point(center);
+        }
+        if !has_exit {
+            built_map.amulet_start = built_map
+                .map
+                .find_most_distant(built_map.player_start, max_depth);
+        }
+        built_map
+    }
+}
+
+impl FixedMapBuilder {
+    /// Reads the map file and checks that it fits a `width` by `height` map
+    pub fn load(&mut self, width: i32, height: i32) -> Result<(), String> {
+        self.text = read_to_string(&self.file)
+            .map_err(|e| format!("Failed opening map file {}: {e}", self.file))?
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+        self.validate(width, height)
+    }
+
+    /// The text has to fit inside the map's outer wall and only use known glyphs
+    fn validate(&self, width: i32, height: i32) -> Result<(), String> {
+        let text_width = self
+            .text
+            .iter()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(0) as i32;
+        let text_height = self.text.len() as i32;
+        if text_width > width - 2 || text_height > height - 2 {
+            return Err(format!(
+                "Map file {} is {text_width}x{text_height}, more than fits in a {width}x{height} map",
+                self.file
+            ));
+        }
+
+        self.text
+            .iter()
+            .flat_map(|row| row.chars())
+            .try_for_each(|c| {
+                legend_glyph(&self.legend, c).map(|_| ()).ok_or_else(|| {
+                    format!(
+                        "Map file {} has no idea what to do with [{}] ({})",
+                        self.file, c, c as i32
+                    )
+                })
+            })
+    }
+
+    fn glyph(&self, c: char) -> PrefabGlyph {
+        legend_glyph(&self.legend, c).unwrap_or_else(|| {
+            panic!(
+                "Map file {} has no idea what to do with [{}] ({})",
+                self.file, c, c as i32
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::prelude::*;
+
+    fn builder(text: &[&str]) -> FixedMapBuilder {
+        FixedMapBuilder {
+            file: "test.txt".to_string(),
+            legend: HashMap::from([('g', PrefabGlyph::Entity("Goblin".to_string()))]),
+            text: text.iter().map(|row| row.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_legend() {
+        assert!(builder(&["#####", "#@g>#", "#####"])
+            .validate(10, 10)
+            .is_ok());
+        assert!(builder(&["#####", "#@x>#", "#####"])
+            .validate(10, 10)
+            .is_err());
+    }
+
+    #[test]
+    fn test_size() {
+        let text = ["########", "#@....>#", "########"];
+        assert!(builder(&text).validate(10, 5).is_ok());
+        assert!(builder(&text).validate(9, 5).is_err());
+        assert!(builder(&text).validate(10, 4).is_err());
+    }
+
+    #[test]
+    fn test_build() {
+        let built_map = builder(&["#@.g>#"]).build(
+            &mut StdRng::seed_from_u64(1),
+            10,
+            5,
+            1024.0,
+            &mut MapGenHistory::default(),
+        );
+        assert_eq!(built_map.player_start, Point::new(3, 2));
+        assert_eq!(built_map.amulet_start, Point::new(6, 2));
+        assert_eq!(built_map.named_spawns.len(), 1);
+    }
+}
@@ -1,9 +1,43 @@
+use std::collections::HashMap;
+
 use crate::prelude::*;
 use serde::Deserialize;
 
+/// What a single character in a prefab's text stands for
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+pub enum PrefabGlyph {
+    Tile(TileType),
+    /// a random spawn drawn from the level's spawn table
+    Spawn,
+    /// a specific entity, named after its `EntityTemplate`
+    Entity(String),
+    PlayerStart,
+    /// the level exit, or the amulet on the final level
+    Exit,
+}
+
+/// Where on the map a prefab is allowed to go
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+pub enum PrefabPlacement {
+    /// anywhere that doesn't cover the player start or the exit
+    Anywhere,
+    /// only entirely on top of existing floor
+    RoomFit,
+    /// within the given walking distance of the exit
+    NearExit(f32),
+}
+
 #[derive(Clone, Deserialize, Debug)]
 pub struct Prefab {
+    pub name: String,
     pub text: Vec<String>,
+    #[serde(default)]
+    pub legend: HashMap<char, PrefabGlyph>,
+    pub placement: PrefabPlacement,
+    #[serde(default)]
+    pub min_player_distance: f32,
+    pub min_count: usize,
+    pub max_count: usize,
 }
 impl Prefab {
     pub fn width(&self) -> i32 {
@@ -14,69 +48,273 @@ impl Prefab {
         self.text.len() as i32
     }
 
+    pub fn glyph(&self, c: char) -> PrefabGlyph {
+        legend_glyph(&self.legend, c).unwrap_or_else(|| {
+            panic!(
+                "Prefab {} has no idea what to do with [{}] ({})",
+                self.name, c, c as i32
+            )
+        })
+    }
+
+    /// Places between min_count and max_count copies of this prefab, never overlapping
+    /// anything in `reserved`. Returns the number of copies that could not be placed
     pub fn apply_prefab(
         &self,
-        map: &mut Map,
-        rng: &mut ThreadRng,
-        player_start: Point,
-        amulet_start: Point,
-        entity_spawns: &mut Vec<Point>,
+        built_map: &mut BuiltMap,
+        rng: &mut StdRng,
+        reserved: &mut Vec<IRect>,
         max_depth: f32,
-    ) {
-        let mut placement = None;
-        let djikstra_map = map.dijkstra_map(player_start, max_depth);
+        history: &mut MapGenHistory,
+    ) -> usize {
+        let count = rng.gen_range(self.min_count..=self.max_count);
+        let mut failures = 0;
 
+        for _ in 0..count {
+            let mut candidates = self.candidate_placements(built_map, reserved, max_depth);
+            let placement = loop {
+                let Some(idx) = rng.random_slice_index(&candidates) else {
+                    break None;
+                };
+                let dimensions = candidates.swap_remove(idx);
+                if self.hides_spawns(built_map, &dimensions) {
+                    break Some(dimensions);
+                }
+            };
+
+            match placement {
+                Some(dimensions) => {
+                    built_map
+                        .entity_spawns
+                        .retain(|(pt, _)| !dimensions.in_bounds(*pt));
+                    built_map
+                        .named_spawns
+                        .retain(|(pt, _)| !dimensions.in_bounds(*pt));
+                    built_map
+                        .rooms
+                        .iter_mut()
+                        .for_each(|room| room.tiles.retain(|pt| !dimensions.in_bounds(*pt)));
+
+                    self.stamp(built_map, dimensions.upper_left());
+                    reserved.push(dimensions);
+                    history.record(format!("Prefab {}", self.name), &built_map.map);
+
+                    let player_start = built_map.player_start;
+                    built_map
+                        .map
+                        .connect_disconnected(player_start, rng, max_depth, history);
+                }
+                None => {
+                    history.prefab_failures += 1;
+                    failures += 1;
+                }
+            }
+        }
+
+        failures
+    }
+
+    fn candidate_placements(
+        &self,
+        built_map: &mut BuiltMap,
+        reserved: &[IRect],
+        max_depth: f32,
+    ) -> Vec<IRect> {
+        let player_map = built_map
+            .map
+            .dijkstra_map(built_map.player_start, max_depth);
+        let exit_map = match self.placement {
+            PrefabPlacement::NearExit(_) => Some(
+                built_map
+                    .map
+                    .uncached_dijkstra_map(built_map.amulet_start, max_depth),
+            ),
+            _ => None,
+        };
+
+        let map = &built_map.map;
+        let walled_rect = map.walled_rect();
         let width = self.width();
         let height = self.height();
 
-        let mut attempts = 0;
-        while placement.is_none() && attempts < 10 {
-            let dimensions = IRect::with_size(
-                rng.gen_range(1..map.width() - width - 1),
-                rng.gen_range(1..map.height() - height - 1),
-                width,
-                height,
-            );
-            if !dimensions.in_bounds(amulet_start) {
-                let mut can_place = false;
-
-                dimensions.points().for_each(|pt| {
-                    let idx = map.point_to_index(pt);
-                    let distance = djikstra_map.map[idx];
-                    if distance > 20.0 {
-                        can_place = true;
+        let mut candidates = Vec::new();
+        for y in walled_rect.y1..=walled_rect.y2 - height {
+            for x in walled_rect.x1..=walled_rect.x2 - width {
+                let dimensions = IRect::with_size(x, y, width, height);
+
+                if dimensions.in_bounds(built_map.player_start)
+                    || dimensions.in_bounds(built_map.amulet_start)
+                    || reserved.iter().any(|r| r.intersect(&dimensions))
+                {
+                    continue;
+                }
+
+                let far_enough = dimensions
+                    .points()
+                    .all(|pt| player_map.map[map.point_to_index(pt)] >= self.min_player_distance);
+
+                let fits = match self.placement {
+                    PrefabPlacement::Anywhere => true,
+                    PrefabPlacement::RoomFit => dimensions
+                        .points()
+                        .all(|pt| map.tile_at(pt) == TileType::Floor),
+                    PrefabPlacement::NearExit(distance) => {
+                        let exit_map = exit_map.as_ref().unwrap();
+                        dimensions
+                            .points()
+                            .any(|pt| exit_map.map[map.point_to_index(pt)] <= distance)
                     }
-                });
+                };
 
-                if can_place {
-                    placement = Some(Point::new(dimensions.x1, dimensions.y1));
-                    entity_spawns.retain(|pt| !dimensions.in_bounds(*pt));
+                if far_enough && fits {
+                    candidates.push(dimensions);
                 }
             }
-
-            attempts += 1;
         }
+        candidates
+    }
 
-        if let Some(placement) = placement {
-            for (ty, row) in self.text.iter().enumerate() {
-                for (tx, c) in row.chars().enumerate() {
-                    let pt = Point::new(placement.x + (tx as i32), placement.y + (ty as i32));
-                    let idx = map.point_to_index(pt);
-                    match c {
-                        'M' => {
-                            map.tiles[idx] = TileType::Floor;
-                            entity_spawns.push(pt);
-                        }
-                        '.' => {
-                            map.tiles[idx] = TileType::Floor;
-                        }
-                        '#' => {
-                            map.tiles[idx] = TileType::Wall;
-                        }
-                        _ => panic!("No idea what to do with [{}] ({})", c, c as i32),
+    /// Whether the spawns of a copy placed at `dimensions` would be out of
+    /// sight of the player start
+    fn hides_spawns(&self, built_map: &BuiltMap, dimensions: &IRect) -> bool {
+        let mut spawns = Vec::new();
+        let mut map = built_map.map.clone();
+        for (ty, row) in self.text.iter().enumerate() {
+            for (tx, c) in row.chars().enumerate() {
+                let pt = dimensions.upper_left() + Point::new(tx as i32, ty as i32);
+                match self.glyph(c) {
+                    PrefabGlyph::Tile(tile) => map.set_tile(pt, tile),
+                    PrefabGlyph::Spawn | PrefabGlyph::Entity(_) => {
+                        map.set_tile(pt, TileType::Floor);
+                        spawns.push(pt);
                     }
+                    _ => map.set_tile(pt, TileType::Floor),
                 }
             }
         }
+        if spawns.is_empty() {
+            return true;
+        }
+
+        let player_start = built_map.player_start;
+        let in_view = field_of_view_set(player_start, map.width().max(map.height()), &map);
+        spawns.iter().all(|pt| !in_view.contains(pt))
+    }
+
+    fn stamp(&self, built_map: &mut BuiltMap, placement: Point) {
+        for (ty, row) in self.text.iter().enumerate() {
+            for (tx, c) in row.chars().enumerate() {
+                let pt = Point::new(placement.x + (tx as i32), placement.y + (ty as i32));
+                built_map.apply_glyph(pt, &self.glyph(c));
+            }
+        }
+    }
+}
+
+/// Looks a character up in a legend, falling back to the default legend
+pub fn legend_glyph(legend: &HashMap<char, PrefabGlyph>, c: char) -> Option<PrefabGlyph> {
+    legend.get(&c).cloned().or_else(|| default_glyph(c))
+}
+
+/// The legend every prefab understands without having to declare it
+pub fn default_glyph(c: char) -> Option<PrefabGlyph> {
+    match c {
+        '.' => Some(PrefabGlyph::Tile(TileType::Floor)),
+        '#' => Some(PrefabGlyph::Tile(TileType::Wall)),
+        'M' => Some(PrefabGlyph::Spawn),
+        '@' => Some(PrefabGlyph::PlayerStart),
+        '>' => Some(PrefabGlyph::Exit),
+        '<' => Some(PrefabGlyph::Tile(TileType::UpStairs)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::prelude::*;
+
+    fn prefab(placement: PrefabPlacement) -> Prefab {
+        Prefab {
+            name: "Test".to_string(),
+            text: vec!["...".to_string(), ".g.".to_string(), "...".to_string()],
+            legend: HashMap::from([('g', PrefabGlyph::Entity("Goblin".to_string()))]),
+            placement,
+            min_player_distance: 0.0,
+            min_count: 1,
+            max_count: 1,
+        }
+    }
+
+    fn built_map(tile: TileType) -> BuiltMap {
+        let mut map = Map::new(12, 12, tile);
+        map.set_tile(Point::new(1, 1), TileType::Floor);
+        map.set_tile(Point::new(10, 10), TileType::Floor);
+        BuiltMap {
+            map,
+            entity_spawns: Vec::new(),
+            named_spawns: Vec::new(),
+            rooms: Vec::new(),
+            spawn_limits: SpawnLimits::default(),
+            player_start: Point::new(1, 1),
+            amulet_start: Point::new(10, 10),
+        }
+    }
+
+    #[test]
+    fn test_glyph() {
+        let prefab = prefab(PrefabPlacement::Anywhere);
+        assert_eq!(prefab.glyph('.'), PrefabGlyph::Tile(TileType::Floor));
+        assert_eq!(prefab.glyph('M'), PrefabGlyph::Spawn);
+        assert_eq!(prefab.glyph('g'), PrefabGlyph::Entity("Goblin".to_string()));
+    }
+
+    #[test]
+    fn test_anywhere() {
+        let mut built_map = built_map(TileType::Wall);
+        let mut reserved = Vec::new();
+        let failures = prefab(PrefabPlacement::Anywhere).apply_prefab(
+            &mut built_map,
+            &mut StdRng::seed_from_u64(1),
+            &mut reserved,
+            1024.0,
+            &mut MapGenHistory::default(),
+        );
+
+        assert_eq!(failures, 0);
+        assert_eq!(reserved.len(), 1);
+        assert_eq!(built_map.named_spawns.len(), 1);
+        assert!(reserved[0].in_bounds(built_map.named_spawns[0].0));
+    }
+
+    #[test]
+    fn test_room_fit_needs_floor() {
+        let mut built_map = built_map(TileType::Wall);
+        let failures = prefab(PrefabPlacement::RoomFit).apply_prefab(
+            &mut built_map,
+            &mut StdRng::seed_from_u64(1),
+            &mut Vec::new(),
+            1024.0,
+            &mut MapGenHistory::default(),
+        );
+
+        assert_eq!(failures, 1);
+        assert!(built_map.named_spawns.is_empty());
+    }
+
+    #[test]
+    fn test_spawns_out_of_sight() {
+        let mut built_map = built_map(TileType::Floor);
+        let failures = prefab(PrefabPlacement::Anywhere).apply_prefab(
+            &mut built_map,
+            &mut StdRng::seed_from_u64(1),
+            &mut Vec::new(),
+            1024.0,
+            &mut MapGenHistory::default(),
+        );
+
+        assert_eq!(failures, 1);
+        assert!(built_map.named_spawns.is_empty());
     }
 }
@@ -0,0 +1,334 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Monsters never spawn closer than this to the player start
+const MIN_MONSTER_DISTANCE: f32 = 10.0;
+
+/// A part of the map that is populated as a whole
+#[derive(Clone, Debug)]
+pub struct Room {
+    pub tiles: Vec<Point>,
+    pub is_corridor: bool,
+}
+impl Room {
+    pub fn from_rect(map: &Map, rect: IRect) -> Self {
+        Self {
+            tiles: rect
+                .points()
+                .filter(|pt| map.tile_at(*pt) == TileType::Floor)
+                .collect(),
+            is_corridor: false,
+        }
+    }
+
+    /// All the floor not inside `rooms`, e.g. the corridors between them
+    pub fn leftover_floor(map: &Map, rooms: &[Room]) -> Self {
+        let in_rooms = rooms
+            .iter()
+            .flat_map(|room| room.tiles.iter().copied())
+            .collect::<HashSet<Point>>();
+        Self {
+            tiles: floor_tiles(map)
+                .filter(|pt| !in_rooms.contains(pt))
+                .collect(),
+            is_corridor: true,
+        }
+    }
+
+    /// The whole floor of the map as a single room
+    pub fn whole_map(map: &Map) -> Vec<Room> {
+        vec![Self {
+            tiles: floor_tiles(map).collect(),
+            is_corridor: false,
+        }]
+    }
+}
+
+/// Splits maps without real rooms, like caves, into `count` regions around
+/// random floor tiles. Every floor tile belongs to the closest of them
+pub fn voronoi_rooms(map: &Map, rng: &mut StdRng, count: usize) -> Vec<Room> {
+    let floor = floor_tiles(map).collect::<Vec<Point>>();
+    let seeds = (0..count)
+        .filter_map(|_| rng.random_slice_entry(&floor).copied())
+        .collect::<Vec<Point>>();
+
+    let mut rooms = vec![
+        Room {
+            tiles: Vec::new(),
+            is_corridor: false,
+        };
+        seeds.len()
+    ];
+    floor.iter().for_each(|pt| {
+        if let Some((closest, _)) = seeds
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, seed)| seed.pythagorean_squared_distance(*pt))
+        {
+            rooms[closest].tiles.push(*pt);
+        }
+    });
+
+    rooms.retain(|room| !room.tiles.is_empty());
+    rooms
+}
+
+fn floor_tiles(map: &Map) -> impl Iterator<Item = Point> + '_ {
+    map.world_rect()
+        .points()
+        .filter(|pt| map.tile_at(*pt) == TileType::Floor)
+}
+
+/// What a spawn point should be filled with
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum SpawnKind {
+    Monster,
+    Item,
+    /// placed by hand, e.g. in a prefab, and filled with either
+    Any,
+}
+
+/// How a room is populated, e.g. a treasure room full of items
+#[derive(Clone, Deserialize, Debug)]
+pub struct RoomTheme {
+    pub frequency: f32,
+    /// corridor themes are only given to corridors, the others only to rooms
+    #[serde(default)]
+    pub corridor: bool,
+    /// how strongly monsters and items are drawn to rooms with this theme
+    pub monster_weight: f32,
+    pub item_weight: f32,
+    /// the most spawns per floor tile
+    pub max_density: f32,
+}
+
+/// Where populating a level may put the spawns that spread out around the
+/// spawn points: monsters stay out of sight of the player start, and rooms
+/// keep to the density of their theme
+#[derive(Clone, Debug, Default)]
+pub struct SpawnLimits {
+    monster_tiles: HashSet<Point>,
+    room_of: HashMap<Point, usize>,
+    capacity: Vec<usize>,
+}
+impl SpawnLimits {
+    /// Monsters may stand on any floor out of sight of, and not too close to,
+    /// `player_start`. Nothing limits the rooms yet
+    pub fn new(map: &Map, player_start: Point) -> Self {
+        let in_view = field_of_view_set(player_start, map.width().max(map.height()), map);
+        Self {
+            monster_tiles: floor_tiles(map)
+                .filter(|pt| {
+                    !in_view.contains(pt)
+                        && pt.pythagorean_distance(player_start) > MIN_MONSTER_DISTANCE
+                })
+                .collect(),
+            room_of: HashMap::new(),
+            capacity: Vec::new(),
+        }
+    }
+
+    /// Allows at most `capacity` more spawns on `tiles`
+    pub fn add_room(&mut self, tiles: &[Point], capacity: usize) {
+        let room = self.capacity.len();
+        self.capacity.push(capacity);
+        self.room_of.extend(tiles.iter().map(|pt| (*pt, room)));
+    }
+
+    pub fn hides_monster(&self, pt: Point) -> bool {
+        self.monster_tiles.contains(&pt)
+    }
+
+    /// Uses up a room's space for a spawn on `pt`, if it may go there
+    pub fn claim(&mut self, pt: Point, is_monster: bool) -> bool {
+        if is_monster && !self.hides_monster(pt) {
+            return false;
+        }
+        match self.room_of.get(&pt) {
+            Some(room) if self.capacity[*room] == 0 => false,
+            Some(room) => {
+                self.capacity[*room] -= 1;
+                true
+            }
+            None => true,
+        }
+    }
+}
+
+/// A room with the theme it was given and the spawns it still has space for
+struct ThemedRoom<'a> {
+    room: usize,
+    theme: &'a RoomTheme,
+    capacity: usize,
+    monster_tiles: Vec<Point>,
+    item_tiles: Vec<Point>,
+}
+impl<'a> ThemedRoom<'a> {
+    fn weight(&self, kind: SpawnKind) -> f32 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        match kind {
+            SpawnKind::Monster => self.theme.monster_weight * self.monster_tiles.len() as f32,
+            _ => self.theme.item_weight * self.item_tiles.len() as f32,
+        }
+    }
+}
+
+impl BuiltMap {
+    /// Gives every room a random theme and scatters spawn points for `monsters`
+    /// and `items` over the rooms whose themes want them, never exceeding a
+    /// theme's density. Monsters are kept out of sight of the player start.
+    /// What is left of each room's space ends up in `spawn_limits`
+    pub fn place_spawns(
+        &mut self,
+        themes: &[RoomTheme],
+        monsters: usize,
+        items: usize,
+        rng: &mut StdRng,
+    ) {
+        let map = &self.map;
+        let mut limits = SpawnLimits::new(map, self.player_start);
+        let taken = self
+            .entity_spawns
+            .iter()
+            .map(|(pt, _)| *pt)
+            .chain(self.named_spawns.iter().map(|(pt, _)| *pt))
+            .chain([self.player_start, self.amulet_start])
+            .collect::<HashSet<Point>>();
+
+        let mut rooms = self
+            .rooms
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, room)| {
+                let candidates = themes
+                    .iter()
+                    .filter(|theme| theme.corridor == room.is_corridor)
+                    .collect::<Vec<&RoomTheme>>();
+                let weights = candidates.iter().map(|t| t.frequency).collect::<Vec<f32>>();
+                let theme = candidates[rng.random_weighted_index(&weights)?];
+
+                let item_tiles = room
+                    .tiles
+                    .iter()
+                    .filter(|pt| map.tile_at(**pt) == TileType::Floor && !taken.contains(pt))
+                    .copied()
+                    .collect::<Vec<Point>>();
+                let monster_tiles = item_tiles
+                    .iter()
+                    .filter(|pt| limits.hides_monster(**pt))
+                    .copied()
+                    .collect();
+
+                Some(ThemedRoom {
+                    room: idx,
+                    theme,
+                    capacity: (item_tiles.len() as f32 * theme.max_density) as usize,
+                    monster_tiles,
+                    item_tiles,
+                })
+            })
+            .collect::<Vec<ThemedRoom>>();
+
+        let kinds = vec![SpawnKind::Monster; monsters]
+            .into_iter()
+            .chain(vec![SpawnKind::Item; items]);
+        for kind in kinds {
+            let weights = rooms.iter().map(|r| r.weight(kind)).collect::<Vec<f32>>();
+            let Some(room) = rng
+                .random_weighted_index(&weights)
+                .map(|idx| &mut rooms[idx])
+            else {
+                continue;
+            };
+
+            let tiles = match kind {
+                SpawnKind::Monster => &room.monster_tiles,
+                _ => &room.item_tiles,
+            };
+            let pt = tiles[rng.gen_range(0..tiles.len())];
+
+            room.capacity -= 1;
+            room.monster_tiles.retain(|t| *t != pt);
+            room.item_tiles.retain(|t| *t != pt);
+            self.entity_spawns.push((pt, kind));
+        }
+
+        rooms
+            .iter()
+            .for_each(|room| limits.add_room(&self.rooms[room.room].tiles, room.capacity));
+        self.spawn_limits = limits;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn theme(monster_weight: f32, item_weight: f32, max_density: f32) -> RoomTheme {
+        RoomTheme {
+            frequency: 1.0,
+            corridor: false,
+            monster_weight,
+            item_weight,
+            max_density,
+        }
+    }
+
+    fn open_map() -> BuiltMap {
+        let mut map = Map::new(40, 20, TileType::Wall);
+        map.clear_rect(IRect::with_size(1, 1, 38, 18));
+        // a wall hiding the right hand side from the player
+        map.set_rect(IRect::with_size(20, 0, 1, 19), TileType::Wall);
+        let rooms = vec![
+            Room::from_rect(&map, IRect::with_size(1, 1, 19, 18)),
+            Room::from_rect(&map, IRect::with_size(21, 1, 18, 18)),
+        ];
+        BuiltMap {
+            map,
+            entity_spawns: Vec::new(),
+            named_spawns: Vec::new(),
+            rooms,
+            spawn_limits: SpawnLimits::default(),
+            player_start: Point::new(2, 2),
+            amulet_start: Point::new(38, 18),
+        }
+    }
+
+    #[test]
+    fn test_monsters_out_of_sight() {
+        let mut built_map = open_map();
+        built_map.place_spawns(
+            &[theme(1.0, 0.0, 1.0)],
+            30,
+            0,
+            &mut StdRng::seed_from_u64(1),
+        );
+
+        assert_eq!(built_map.entity_spawns.len(), 30);
+        assert!(built_map.entity_spawns.iter().all(|(pt, _)| pt.x > 20));
+    }
+
+    #[test]
+    fn test_density_limit() {
+        let mut built_map = open_map();
+        built_map.place_spawns(
+            &[theme(1.0, 1.0, 0.01)],
+            100,
+            100,
+            &mut StdRng::seed_from_u64(1),
+        );
+
+        // 341 free floor tiles in the first room and 323 in the second
+        assert_eq!(built_map.entity_spawns.len(), 3 + 3);
+        let items = built_map
+            .entity_spawns
+            .iter()
+            .filter(|(_, kind)| *kind == SpawnKind::Item)
+            .count();
+        assert_eq!(items, 3);
+    }
+}
@@ -9,30 +9,42 @@ pub struct SquareMapBuilder {
 impl MapBuilder for SquareMapBuilder {
     fn build(
         &self,
-        rng: &mut ThreadRng,
+        rng: &mut StdRng,
         width: i32,
         height: i32,
-        num_monsters: usize,
         max_depth: f32,
+        history: &mut MapGenHistory,
     ) -> BuiltMap {
         let mut map = Map::new(width, height, TileType::Wall);
 
-        let rooms = self.build_random_rooms(&mut map, rng);
-        Self::build_corridors(&mut map, rng, &rooms);
+        let rooms = self.build_random_rooms(&mut map, rng, history);
+        Self::build_corridors(&mut map, rng, &rooms, history);
         let player_start = rooms[0].center();
         let amulet_start = map.find_most_distant(player_start, max_depth);
 
-        let entity_spawns = determine_entity_spawn_points(&map, player_start, rng, num_monsters);
+        let mut rooms = rooms
+            .iter()
+            .map(|rect| Room::from_rect(&map, *rect))
+            .collect::<Vec<Room>>();
+        rooms.push(Room::leftover_floor(&map, &rooms));
         BuiltMap {
             map,
-            entity_spawns,
+            entity_spawns: Vec::new(),
+            named_spawns: Vec::new(),
+            rooms,
+            spawn_limits: SpawnLimits::default(),
             player_start,
             amulet_start,
         }
     }
 }
 impl SquareMapBuilder {
-    fn build_random_rooms(&self, map: &mut Map, rng: &mut ThreadRng) -> Vec<IRect> {
+    fn build_random_rooms(
+        &self,
+        map: &mut Map,
+        rng: &mut StdRng,
+        history: &mut MapGenHistory,
+    ) -> Vec<IRect> {
         let mut rooms = Vec::<IRect>::with_capacity(self.num_rooms);
 
         while rooms.len() < self.num_rooms {
@@ -50,6 +62,7 @@ impl SquareMapBuilder {
             if !overlap {
                 map.clear_rect(room);
                 rooms.push(room);
+                history.record(format!("Room {}", rooms.len()), map);
             }
         }
 
@@ -68,7 +81,12 @@ impl SquareMapBuilder {
         map.clear_rect(IRect::with_size(min(x1, x2), y, (x2 - x1).abs() + 1, 1));
     }
 
-    fn build_corridors(map: &mut Map, rng: &mut ThreadRng, rooms: &Vec<IRect>) {
+    fn build_corridors(
+        map: &mut Map,
+        rng: &mut StdRng,
+        rooms: &[IRect],
+        history: &mut MapGenHistory,
+    ) {
         let index_pairs = (0..rooms.len() - 1).map(|fst| (fst, fst + 1));
 
         for (idx1, idx2) in index_pairs {
@@ -82,6 +100,7 @@ impl SquareMapBuilder {
                 Self::apply_vertical_tunnel(map, center1.y, center2.y, center1.x);
                 Self::apply_horizontal_tunnel(map, center1.x, center2.x, center2.y);
             }
+            history.record(format!("Corridor {} to {}", idx1 + 1, idx2 + 1), map);
         }
     }
 }
@@ -0,0 +1,256 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+use serde::Serialize;
+
+use crate::prelude::*;
+
+const TILE_TINT: (u8, u8, u8) = (255, 255, 191);
+const PLAYER_TINT: (u8, u8, u8) = (255, 255, 0);
+const AMULET_TINT: (u8, u8, u8) = (255, 0, 255);
+const SPAWN_TINT: (u8, u8, u8) = (255, 64, 64);
+
+/// Everything needed to reproduce a generated map. `tiles` uses the
+/// default prefab legend, so it can be loaded by a `FixedMapBuilder`
+#[derive(Serialize)]
+pub struct MapExport<'a> {
+    pub level: &'a str,
+    pub seed: u64,
+    pub width: i32,
+    pub height: i32,
+    pub tiles: Vec<String>,
+    pub player_start: Point,
+    pub amulet_start: Point,
+    pub entity_spawns: &'a [(Point, SpawnKind)],
+    pub named_spawns: &'a [(Point, String)],
+}
+impl<'a> MapExport<'a> {
+    pub fn new(level: &'a str, seed: u64, built_map: &'a BuiltMap) -> Self {
+        let map = &built_map.map;
+        let tiles = (0..map.height())
+            .map(|y| {
+                (0..map.width())
+                    .map(|x| match map.tile_at(Point::new(x, y)) {
+                        TileType::Wall => '#',
+                        TileType::Floor => '.',
+                        TileType::Exit => '>',
+                        TileType::UpStairs => '<',
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            level,
+            seed,
+            width: map.width(),
+            height: map.height(),
+            tiles,
+            player_start: built_map.player_start,
+            amulet_start: built_map.amulet_start,
+            entity_spawns: &built_map.entity_spawns,
+            named_spawns: &built_map.named_spawns,
+        }
+    }
+}
+
+/// Builds a level and writes it to `<out>.png`, `<out>.json` and `<out>.ron`.
+/// `target` is found with `GameData::find_level`
+pub fn map_export(
+    gamedata: GameData,
+    target: Option<&String>,
+    seed: Option<&String>,
+    out: Option<&String>,
+) -> BError {
+    let level = gamedata.find_level(target)?;
+    let seed = match seed {
+        Some(seed) => seed.parse()?,
+        None => rand::random(),
+    };
+    let out = match out {
+        Some(out) => out.clone(),
+        None => format!("map_{seed}"),
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let built_map = gamedata.build_level(&level, &mut rng, &mut MapGenHistory::default());
+    let export = MapExport::new(&level.name, seed, &built_map);
+
+    serde_json::to_writer_pretty(
+        BufWriter::new(File::create(format!("{out}.json"))?),
+        &export,
+    )?;
+    std::fs::write(
+        format!("{out}.ron"),
+        ron::ser::to_string_pretty(&export, ron::ser::PrettyConfig::default())?,
+    )?;
+
+    let font = GlyphFont::load(
+        &format!("resources/{}", gamedata.tile_font_file),
+        gamedata.tile_width as usize,
+        gamedata.tile_height as usize,
+    )?;
+    let image = render_map(
+        &gamedata,
+        &font,
+        &built_map,
+        level.get_theme(&gamedata),
+        level.has_amulet,
+    );
+    image.save(&format!("{out}.png"))?;
+
+    println!(
+        "Exported {} (seed {seed}) to {out}.png, {out}.json and {out}.ron",
+        level.name
+    );
+    Ok(())
+}
+
+fn render_map(
+    gamedata: &GameData,
+    font: &GlyphFont,
+    built_map: &BuiltMap,
+    theme: &MapTheme,
+    is_final_level: bool,
+) -> RgbaImage {
+    let map = &built_map.map;
+    let mut image = RgbaImage::new(
+        map.width() as usize * font.glyph_width,
+        map.height() as usize * font.glyph_height,
+    );
+
+    map.world_rect().points().for_each(|pt| {
+        let glyph = theme.tile_to_render(map.tile_at(pt), Revealed::Seen);
+        font.draw(&mut image, pt, glyph, TILE_TINT);
+    });
+
+    built_map
+        .entity_spawns
+        .iter()
+        .for_each(|(pt, _)| font.draw(&mut image, *pt, 'M', SPAWN_TINT));
+    built_map.named_spawns.iter().for_each(|(pt, name)| {
+        font.draw(
+            &mut image,
+            *pt,
+            gamedata.entity_template(name).glyph,
+            SPAWN_TINT,
+        )
+    });
+
+    let amulet_glyph = if is_final_level {
+        gamedata.amulet_template.glyph
+    } else {
+        theme.tile_to_render(TileType::Exit, Revealed::Seen)
+    };
+    font.draw(
+        &mut image,
+        built_map.amulet_start,
+        amulet_glyph,
+        AMULET_TINT,
+    );
+    font.draw(
+        &mut image,
+        built_map.player_start,
+        gamedata.player_template.glyph,
+        PLAYER_TINT,
+    );
+
+    image
+}
+
+struct RgbaImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+impl RgbaImage {
+    fn new(width: usize, height: usize) -> Self {
+        let mut pixels = vec![0; width * height * 4];
+        pixels.chunks_mut(4).for_each(|p| p[3] = 255);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn save(&self, path: &str) -> BError {
+        let mut encoder = png::Encoder::new(
+            BufWriter::new(File::create(path)?),
+            self.width as u32,
+            self.height as u32,
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+}
+
+/// A 16x16 grid of code page 437 glyphs, like the fonts bracket-lib uses
+struct GlyphFont {
+    image: RgbaImage,
+    glyph_width: usize,
+    glyph_height: usize,
+}
+impl GlyphFont {
+    fn load(
+        path: &str,
+        glyph_width: usize,
+        glyph_height: usize,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|p| [*p, *p, *p, 255]).collect(),
+            png::ColorType::Indexed => return Err(format!("Unexpected palette in {path}").into()),
+        };
+
+        Ok(Self {
+            image: RgbaImage {
+                width: info.width as usize,
+                height: info.height as usize,
+                pixels,
+            },
+            glyph_width,
+            glyph_height,
+        })
+    }
+
+    /// Blends a tinted glyph over the tile at `pt`
+    fn draw(&self, target: &mut RgbaImage, pt: Point, glyph: char, tint: (u8, u8, u8)) {
+        let glyph = to_cp437(glyph) as usize;
+        let source_x = (glyph % 16) * self.glyph_width;
+        let source_y = (glyph / 16) * self.glyph_height;
+        let target_x = pt.x as usize * self.glyph_width;
+        let target_y = pt.y as usize * self.glyph_height;
+        let tint = [tint.0, tint.1, tint.2];
+
+        for y in 0..self.glyph_height {
+            for x in 0..self.glyph_width {
+                let source = ((source_y + y) * self.image.width + source_x + x) * 4;
+                let target_idx = ((target_y + y) * target.width + target_x + x) * 4;
+                let alpha = self.image.pixels[source + 3] as u32;
+                for (c, tint) in tint.iter().enumerate() {
+                    let color = self.image.pixels[source + c] as u32 * *tint as u32 / 255;
+                    let background = target.pixels[target_idx + c] as u32;
+                    target.pixels[target_idx + c] =
+                        ((color * alpha + background * (255 - alpha)) / 255) as u8;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use std::time::Instant;
+
+use crate::prelude::*;
+
+/// Measurements of a single generated map
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapStats {
+    pub floor_ratio: f32,
+    /// walking distance from the player start to the amulet
+    pub path_length: f32,
+    pub dead_ends: usize,
+    /// spawns per walkable tile
+    pub spawn_density: f32,
+    pub prefab_failures: usize,
+    pub generation_ms: f32,
+}
+impl MapStats {
+    pub fn measure(
+        built_map: &BuiltMap,
+        max_depth: f32,
+        prefab_failures: usize,
+        generation_ms: f32,
+    ) -> Self {
+        let map = &built_map.map;
+        let walkable = walkable_tiles(map);
+        let path_length = map
+            .uncached_dijkstra_map(built_map.player_start, max_depth)
+            .map[map.point_to_index(built_map.amulet_start)];
+        let spawns = built_map.entity_spawns.len() + built_map.named_spawns.len();
+
+        Self {
+            floor_ratio: walkable as f32 / map.tiles.len() as f32,
+            path_length,
+            dead_ends: count_dead_ends(map),
+            spawn_density: spawns as f32 / walkable.max(1) as f32,
+            prefab_failures,
+            generation_ms,
+        }
+    }
+
+    /// Describes every way these stats break the validation limits
+    pub fn violations(&self, validation: &MapValidation) -> Vec<String> {
+        let mut violations = Vec::new();
+        if self.floor_ratio < validation.min_floor_ratio {
+            violations.push(format!("floor ratio {:.3} too low", self.floor_ratio));
+        }
+        if self.floor_ratio > validation.max_floor_ratio {
+            violations.push(format!("floor ratio {:.3} too high", self.floor_ratio));
+        }
+        if self.path_length == UNREACHABLE {
+            violations.push("amulet is unreachable".to_string());
+        } else if self.path_length < validation.min_path_length {
+            violations.push(format!("path length {} too short", self.path_length));
+        }
+        if self.dead_ends > validation.max_dead_ends {
+            violations.push(format!("{} dead ends", self.dead_ends));
+        }
+        if self.spawn_density > validation.max_spawn_density {
+            violations.push(format!("spawn density {:.3} too high", self.spawn_density));
+        }
+        if self.prefab_failures > validation.max_prefab_failures {
+            violations.push(format!("{} prefabs not placed", self.prefab_failures));
+        }
+        if self.generation_ms > validation.max_generation_ms {
+            violations.push(format!("took {:.1}ms to generate", self.generation_ms));
+        }
+        violations
+    }
+}
+
+fn walkable_tiles(map: &Map) -> usize {
+    (0..map.tiles.len())
+        .filter(|idx| map.can_enter_tile(map.index_to_point(*idx)))
+        .count()
+}
+
+/// Walkable tiles with only a single walkable neighbor
+fn count_dead_ends(map: &Map) -> usize {
+    (0..map.tiles.len())
+        .filter(|idx| {
+            map.can_enter_tile(map.index_to_point(*idx)) && map.get_available_exits(*idx).len() == 1
+        })
+        .count()
+}
+
+/// Generates `samples` maps for every level, printing a summary of their stats.
+/// Fails if any map breaks its level's validation limits
+pub fn mapgen_stats(gamedata: GameData, samples: Option<&String>, seed: Option<&String>) -> BError {
+    let seed: u64 = match seed {
+        Some(seed) => seed.parse()?,
+        None => rand::random(),
+    };
+    println!("Base seed {seed}");
+
+    let mut total_failures = 0;
+    for (level_number, level) in gamedata.game_levels.iter().enumerate() {
+        let validation = level.get_validation(&gamedata);
+        let samples = match samples {
+            Some(samples) => samples.parse()?,
+            None => validation.samples,
+        };
+
+        let mut all_stats = Vec::with_capacity(samples);
+        let mut failures = Vec::new();
+        for sample in 0..samples {
+            let map_seed = seed.wrapping_add(sample as u64);
+            let mut rng = StdRng::seed_from_u64(map_seed);
+            let mut history = MapGenHistory::default();
+
+            let start = Instant::now();
+            let built_map = gamedata.build_level(level, &mut rng, &mut history);
+            let generation_ms = start.elapsed().as_secs_f32() * 1000.0;
+
+            let stats = MapStats::measure(
+                &built_map,
+                gamedata.max_dijkstra_depth,
+                history.prefab_failures,
+                generation_ms,
+            );
+            let violations = stats.violations(validation);
+            if !violations.is_empty() {
+                failures.push((map_seed, violations));
+            }
+            all_stats.push(stats);
+        }
+
+        println!("\n{level_number}: {} ({samples} maps)", level.name);
+        print_summary("floor ratio", &all_stats, |s| s.floor_ratio);
+        print_summary("path length", &all_stats, |s| s.path_length);
+        print_summary("dead ends", &all_stats, |s| s.dead_ends as f32);
+        print_summary("spawn density", &all_stats, |s| s.spawn_density);
+        print_summary("prefab failures", &all_stats, |s| s.prefab_failures as f32);
+        print_summary("generation ms", &all_stats, |s| s.generation_ms);
+
+        failures.iter().for_each(|(map_seed, violations)| {
+            println!("  FAILED seed {map_seed}: {}", violations.join(", "));
+        });
+        total_failures += failures.len();
+    }
+
+    if total_failures > 0 {
+        Err(format!("{total_failures} maps failed validation").into())
+    } else {
+        println!("\nAll maps passed validation");
+        Ok(())
+    }
+}
+
+fn print_summary(label: &str, all_stats: &[MapStats], stat: impl Fn(&MapStats) -> f32) {
+    let values = all_stats
+        .iter()
+        .map(stat)
+        .filter(|v| *v != UNREACHABLE)
+        .collect::<Vec<f32>>();
+    if values.is_empty() {
+        println!("  {label:<16} n/a");
+        return;
+    }
+    let min = values.iter().cloned().fold(f32::MAX, f32::min);
+    let max = values.iter().cloned().fold(f32::MIN, f32::max);
+    let avg = values.iter().sum::<f32>() / values.len() as f32;
+    println!("  {label:<16} min {min:>8.3}  avg {avg:>8.3}  max {max:>8.3}");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::MapStats;
+
+    fn corridor_map() -> BuiltMap {
+        let mut map = Map::new(7, 3, TileType::Wall);
+        map.clear_rect(IRect::with_size(1, 1, 5, 1));
+        BuiltMap {
+            map,
+            entity_spawns: vec![(Point::new(3, 1), SpawnKind::Monster)],
+            named_spawns: Vec::new(),
+            rooms: Vec::new(),
+            spawn_limits: SpawnLimits::default(),
+            player_start: Point::new(1, 1),
+            amulet_start: Point::new(5, 1),
+        }
+    }
+
+    #[test]
+    fn test_measure() {
+        let stats = MapStats::measure(&corridor_map(), 1024.0, 0, 1.0);
+        assert_eq!(stats.floor_ratio, 5.0 / 21.0);
+        assert_eq!(stats.path_length, 4.0);
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.spawn_density, 1.0 / 5.0);
+    }
+
+    #[test]
+    fn test_unreachable_amulet() {
+        let mut built_map = corridor_map();
+        built_map.map.set_tile(Point::new(3, 1), TileType::Wall);
+        let stats = MapStats::measure(&built_map, 1024.0, 0, 1.0);
+        assert_eq!(stats.path_length, UNREACHABLE);
+        assert!(stats
+            .violations(&GameData::load(crate::GAME_DATA_PATH).map_validation)
+            .contains(&"amulet is unreachable".to_string()));
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::stdin;
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::{display, display_with_markers, prelude::*};
+
+const ANIMATION_DELAY: Duration = Duration::from_millis(60);
+
+/// Steps through every snapshot taken while building a map.
+/// `target` is found with `GameData::find_level`, and `seed` makes the build repeatable
+pub fn mapgen_viewer(gamedata: GameData, target: Option<&String>, seed: Option<&String>) -> BError {
+    let level = gamedata.find_level(target)?;
+    let seed = match seed {
+        Some(seed) => seed.parse()?,
+        None => rand::random(),
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut history = MapGenHistory::recording();
+    let built_map = gamedata.build_level(&level, &mut rng, &mut history);
+    let theme = level.get_theme(&gamedata);
+
+    let snapshots = &history.snapshots;
+    let num_steps = snapshots.len() + 1;
+    let mut step = 0;
+    let mut animating = false;
+
+    loop {
+        let title = if step < snapshots.len() {
+            format!(
+                "{} (seed {seed}) step {}/{num_steps}: {}",
+                level.name,
+                step + 1,
+                snapshots[step].label
+            )
+        } else {
+            format!(
+                "{} (seed {seed}) step {num_steps}/{num_steps}: Final map",
+                level.name
+            )
+        };
+
+        if step < snapshots.len() {
+            let mut map = Map::new(
+                built_map.map.width(),
+                built_map.map.height(),
+                TileType::Wall,
+            );
+            map.tiles = snapshots[step].tiles.clone();
+            display_with_markers(&title, &map, &[], theme)?;
+        } else {
+            display(
+                &title,
+                &built_map.map,
+                &built_map.player_start,
+                &built_map.amulet_start,
+                &built_map.entity_spawns,
+                theme,
+            )?;
+        }
+
+        if animating && step + 1 < num_steps {
+            sleep(ANIMATION_DELAY);
+            step += 1;
+            continue;
+        }
+        animating = false;
+
+        println!("[Enter] next  [b] back  [a] animate  [s] start  [q] quit");
+        let mut command = String::new();
+        // the end of the input quits, or piped input would loop forever
+        if stdin().read_line(&mut command)? == 0 {
+            break;
+        }
+        match command.trim() {
+            "q" => break,
+            "b" => step = step.saturating_sub(1),
+            "a" => animating = true,
+            "s" => step = 0,
+            _ => step = (step + 1).min(num_steps - 1),
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,22 @@
-use rand::{rngs::ThreadRng, Rng};
+use bevy::prelude::Resource;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// What a run was started with. The levels, the item appearances and the
+/// `GameRng` are all seeded from it
+#[derive(Clone, Copy, Debug, Resource)]
+pub struct RunSeed(pub u64);
+impl RunSeed {
+    /// Every level gets its own generator, so it comes out the same whatever
+    /// order the levels are visited in
+    pub fn level_rng(&self, level: usize) -> StdRng {
+        StdRng::seed_from_u64(self.0.wrapping_add(level as u64))
+    }
+}
+
+/// Where everything random during play is drawn from. It is seeded from the
+/// `RunSeed`, so the same seed and the same moves play out the same way
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
 
 pub trait RngExtension {
     fn random_slice_index<T>(&mut self, slice: &[T]) -> Option<usize>;
@@ -6,9 +24,12 @@ pub trait RngExtension {
     fn random_slice_entry<'slice, T>(&mut self, slice: &'slice [T]) -> Option<&'slice T> {
         self.random_slice_index(slice).map(|index| &slice[index])
     }
+
+    /// Picks an index with a chance proportional to its weight
+    fn random_weighted_index(&mut self, weights: &[f32]) -> Option<usize>;
 }
 
-impl RngExtension for ThreadRng {
+impl<R: Rng> RngExtension for R {
     fn random_slice_index<T>(&mut self, slice: &[T]) -> Option<usize> {
         if slice.is_empty() {
             None
@@ -16,4 +37,26 @@ impl RngExtension for ThreadRng {
             Some(self.gen_range(0..slice.len()))
         }
     }
+
+    fn random_weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().filter(|w| **w > 0.0).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = self.gen_range(0.0..total);
+        weights
+            .iter()
+            .position(|w| {
+                if *w <= 0.0 {
+                    false
+                } else if roll < *w {
+                    true
+                } else {
+                    roll -= *w;
+                    false
+                }
+            })
+            .or_else(|| weights.iter().rposition(|w| *w > 0.0))
+    }
 }
@@ -0,0 +1,375 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::prelude::*;
+use serde::Deserialize;
+
+/// How far from the first member of a group the others may be placed
+const GROUP_RADIUS: f32 = 4.0;
+
+/// A number that changes with the depth of a level: `base` at depth 1,
+/// changing by `per_depth` for each level deeper. It is zero outside of
+/// `min_depth..=max_depth`, and never negative
+#[derive(Clone, Copy, Deserialize, Debug)]
+pub struct DepthCurve {
+    pub base: f32,
+    #[serde(default)]
+    pub per_depth: f32,
+    #[serde(default)]
+    pub min_depth: usize,
+    pub max_depth: Option<usize>,
+}
+impl DepthCurve {
+    pub fn at(&self, depth: usize) -> f32 {
+        if depth < self.min_depth || self.max_depth.is_some_and(|max| depth > max) {
+            0.0
+        } else {
+            (self.base + self.per_depth * (depth as f32 - 1.0)).max(0.0)
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct SpawnEntry {
+    /// the `EntityTemplate` to spawn
+    pub name: String,
+    pub frequency: DepthCurve,
+    /// how many spawn together, e.g. a pack of goblins
+    #[serde(default = "single")]
+    pub group_size: (usize, usize),
+}
+
+fn single() -> (usize, usize) {
+    (1, 1)
+}
+
+/// What the random spawn points of a level are filled with. Monsters and
+/// items have separate budgets, both counted in entities
+#[derive(Clone, Deserialize, Debug)]
+pub struct SpawnTable {
+    pub name: String,
+    pub monster_budget: DepthCurve,
+    pub item_budget: DepthCurve,
+    pub monsters: Vec<SpawnEntry>,
+    pub items: Vec<SpawnEntry>,
+    /// spawned on every level using this table, on top of the budgets
+    #[serde(default)]
+    pub guaranteed_monsters: Vec<String>,
+    #[serde(default)]
+    pub guaranteed_items: Vec<String>,
+}
+impl SpawnTable {
+    pub fn budgets(&self, depth: usize) -> (usize, usize) {
+        (
+            self.monster_budget.at(depth).round() as usize,
+            self.item_budget.at(depth).round() as usize,
+        )
+    }
+
+    /// How many monster and item spawn points a level needs
+    pub fn spawn_counts(&self, depth: usize) -> (usize, usize) {
+        let (monsters, items) = self.budgets(depth);
+        (
+            monsters + self.guaranteed_monsters.len(),
+            items + self.guaranteed_items.len(),
+        )
+    }
+
+    /// Chooses an entry at random, weighted by its frequency at `depth`
+    pub fn pick<'a>(
+        entries: &'a [SpawnEntry],
+        depth: usize,
+        rng: &mut StdRng,
+    ) -> Option<&'a SpawnEntry> {
+        let weights = entries
+            .iter()
+            .map(|e| e.frequency.at(depth))
+            .collect::<Vec<f32>>();
+        rng.random_weighted_index(&weights).map(|idx| &entries[idx])
+    }
+
+    /// Decides which template to spawn where. Hand-placed spawn points are
+    /// filled first, then the guaranteed spawns take the first points of their
+    /// kind and the rest are filled until the budgets are used up. Groups spread
+    /// out onto free floor around their spawn point, as far as `limits` allow.
+    /// Hand-placed points in sight of the player start only get items
+    pub fn populate(
+        &self,
+        depth: usize,
+        map: &Map,
+        spawn_points: &[(Point, SpawnKind)],
+        limits: &SpawnLimits,
+        rng: &mut StdRng,
+    ) -> Vec<(Point, String)> {
+        let mut limits = limits.clone();
+        let (mut monsters_left, mut items_left) = self.budgets(depth);
+        let mut occupied = spawn_points
+            .iter()
+            .map(|(pt, _)| *pt)
+            .collect::<HashSet<Point>>();
+        let mut guaranteed_monsters = self.guaranteed_monsters.iter();
+        let mut guaranteed_items = self.guaranteed_items.iter();
+        let mut spawns = Vec::new();
+
+        let ordered = spawn_points
+            .iter()
+            .filter(|(_, kind)| *kind == SpawnKind::Any)
+            .chain(
+                spawn_points
+                    .iter()
+                    .filter(|(_, kind)| *kind != SpawnKind::Any),
+            );
+
+        for (pt, kind) in ordered {
+            let guaranteed = match kind {
+                SpawnKind::Monster => guaranteed_monsters.next(),
+                SpawnKind::Item => guaranteed_items.next(),
+                SpawnKind::Any => None,
+            };
+            if let Some(name) = guaranteed {
+                spawns.push((*pt, name.clone()));
+                continue;
+            }
+
+            let is_monster = match kind {
+                SpawnKind::Monster => true,
+                SpawnKind::Item => false,
+                SpawnKind::Any => {
+                    let monsters_left = if limits.hides_monster(*pt) {
+                        monsters_left
+                    } else {
+                        0
+                    };
+                    if monsters_left + items_left == 0 {
+                        continue;
+                    }
+                    rng.gen_range(0..monsters_left + items_left) < monsters_left
+                }
+            };
+            let (entries, left) = if is_monster {
+                (&self.monsters, &mut monsters_left)
+            } else {
+                (&self.items, &mut items_left)
+            };
+            if *left == 0 {
+                continue;
+            }
+
+            if let Some(entry) = Self::pick(entries, depth, rng) {
+                let (min, max) = entry.group_size;
+                let size = rng.gen_range(min..=max.max(min)).max(1);
+
+                let mut group = vec![*pt];
+                group.extend(group_points_where(
+                    map,
+                    *pt,
+                    size - 1,
+                    &mut occupied,
+                    |member| limits.claim(member, is_monster),
+                ));
+
+                *left = left.saturating_sub(group.len());
+                spawns.extend(group.into_iter().map(|pt| (pt, entry.name.clone())));
+            }
+        }
+
+        spawns
+    }
+}
+
+/// Finds up to `count` free floor tiles near `leader`, walking outwards,
+/// and marks them as occupied
+pub fn group_points(
+    map: &Map,
+    leader: Point,
+    count: usize,
+    occupied: &mut HashSet<Point>,
+) -> Vec<Point> {
+    group_points_where(map, leader, count, occupied, |_| true)
+}
+
+/// Like `group_points`, but only takes the tiles `allowed` agrees to
+pub fn group_points_where(
+    map: &Map,
+    leader: Point,
+    count: usize,
+    occupied: &mut HashSet<Point>,
+    mut allowed: impl FnMut(Point) -> bool,
+) -> Vec<Point> {
+    let mut found = Vec::new();
+    let mut visited = HashSet::from([leader]);
+    let mut queue = VecDeque::from([leader]);
+
+    while let Some(pt) = queue.pop_front() {
+        if found.len() == count {
+            break;
+        }
+        for (idx, _) in map.get_available_exits(map.point_to_index(pt)) {
+            let next = map.index_to_point(idx);
+            if leader.pythagorean_distance(next) > GROUP_RADIUS || !visited.insert(next) {
+                continue;
+            }
+            if found.len() < count
+                && map.tile_at(next) == TileType::Floor
+                && !occupied.contains(&next)
+                && allowed(next)
+            {
+                occupied.insert(next);
+                found.push(next);
+            }
+            queue.push_back(next);
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::prelude::*;
+
+    fn curve(base: f32, per_depth: f32, min_depth: usize) -> DepthCurve {
+        DepthCurve {
+            base,
+            per_depth,
+            min_depth,
+            max_depth: None,
+        }
+    }
+
+    fn entry(name: &str, frequency: DepthCurve, group_size: (usize, usize)) -> SpawnEntry {
+        SpawnEntry {
+            name: name.to_string(),
+            frequency,
+            group_size,
+        }
+    }
+
+    /// Open floor, with a wall across it hiding everything below from (1, 1)
+    fn hidden_half_map() -> Map {
+        let mut map = Map::new(20, 20, TileType::Floor);
+        map.set_rect(IRect::with_size(0, 5, 20, 1), TileType::Wall);
+        map
+    }
+
+    fn goblin_table(group_size: (usize, usize)) -> SpawnTable {
+        SpawnTable {
+            name: "Test".to_string(),
+            monster_budget: curve(6.0, 0.0, 0),
+            item_budget: curve(6.0, 0.0, 0),
+            monsters: vec![entry("Goblin", curve(1.0, 0.0, 0), group_size)],
+            items: vec![entry("Healing Potion", curve(1.0, 0.0, 0), (1, 1))],
+            guaranteed_monsters: Vec::new(),
+            guaranteed_items: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_depth_curve() {
+        let curve = DepthCurve {
+            max_depth: Some(3),
+            ..curve(2.0, -0.5, 2)
+        };
+        assert_eq!(curve.at(1), 0.0);
+        assert_eq!(curve.at(2), 1.5);
+        assert_eq!(curve.at(3), 1.0);
+        assert_eq!(curve.at(4), 0.0);
+        assert_eq!(self::curve(1.0, -1.0, 0).at(5), 0.0);
+    }
+
+    #[test]
+    fn test_pick_skips_out_of_depth() {
+        let entries = vec![
+            entry("Goblin", curve(1.0, 0.0, 0), (1, 1)),
+            entry("Ettin", curve(1.0, 0.0, 3), (1, 1)),
+        ];
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let picked = SpawnTable::pick(&entries, 1, &mut rng).unwrap();
+            assert_eq!(picked.name, "Goblin");
+        }
+    }
+
+    #[test]
+    fn test_populate_budgets_and_groups() {
+        let table = SpawnTable {
+            name: "Test".to_string(),
+            monster_budget: curve(6.0, 0.0, 0),
+            item_budget: curve(2.0, 0.0, 0),
+            monsters: vec![entry("Goblin", curve(1.0, 0.0, 0), (3, 3))],
+            items: vec![entry("Healing Potion", curve(1.0, 0.0, 0), (1, 1))],
+            guaranteed_monsters: Vec::new(),
+            guaranteed_items: vec!["Dungeon Map".to_string()],
+        };
+        let map = hidden_half_map();
+        let spawn_points = (0..20)
+            .map(|x| {
+                let kind = if x % 2 == 0 {
+                    SpawnKind::Monster
+                } else {
+                    SpawnKind::Item
+                };
+                (Point::new(x, 12), kind)
+            })
+            .collect::<Vec<(Point, SpawnKind)>>();
+
+        let spawns = table.populate(
+            1,
+            &map,
+            &spawn_points,
+            &SpawnLimits::new(&map, Point::new(1, 1)),
+            &mut StdRng::seed_from_u64(1),
+        );
+        let count = |name: &str| spawns.iter().filter(|(_, n)| n == name).count();
+
+        assert_eq!(count("Dungeon Map"), 1);
+        assert_eq!(count("Goblin"), 6);
+        assert_eq!(count("Healing Potion"), 2);
+
+        let positions = spawns.iter().map(|(pt, _)| *pt).collect::<HashSet<Point>>();
+        assert_eq!(positions.len(), spawns.len());
+    }
+
+    #[test]
+    fn test_groups_respect_limits() {
+        let map = hidden_half_map();
+        let mut limits = SpawnLimits::new(&map, Point::new(1, 1));
+        // the leader's room has space for a single follower
+        let room = IRect::with_size(0, 6, 20, 14)
+            .points()
+            .collect::<Vec<Point>>();
+        limits.add_room(&room, 1);
+
+        let spawn_points = [(Point::new(10, 6), SpawnKind::Monster)];
+        let spawns = goblin_table((3, 3)).populate(
+            1,
+            &map,
+            &spawn_points,
+            &limits,
+            &mut StdRng::seed_from_u64(1),
+        );
+
+        assert_eq!(spawns.len(), 2);
+        assert!(spawns.iter().all(|(pt, _)| pt.y > 5));
+    }
+
+    #[test]
+    fn test_visible_hand_placed_points_get_items() {
+        let map = hidden_half_map();
+        let spawn_points = [(Point::new(3, 3), SpawnKind::Any)];
+        for seed in 0..10 {
+            let spawns = goblin_table((1, 1)).populate(
+                1,
+                &map,
+                &spawn_points,
+                &SpawnLimits::new(&map, Point::new(1, 1)),
+                &mut StdRng::seed_from_u64(seed),
+            );
+            assert_eq!(
+                spawns,
+                vec![(Point::new(3, 3), "Healing Potion".to_string())]
+            );
+        }
+    }
+}
@@ -1,23 +1,61 @@
 mod advance_level;
+mod afflictions;
+mod auto_action;
+mod behaviour;
+mod boss;
+mod breath;
 mod chasing;
 mod check_end_of_level;
 mod combat;
+mod dungeon_overview;
 mod end_turn;
 mod entity_renderer;
+mod flee;
+mod follow;
 mod fov;
 mod game_over;
+mod guard;
+mod hear_noise;
 mod hud;
+mod hunger;
 mod init_game;
 mod init_level;
+mod investigate;
+mod keep_distance;
+mod life_drain;
 mod map_renderer;
 mod movement;
+mod notice;
+mod on_hit;
+mod patrol;
 mod player_input;
+mod provoke;
+mod regeneration;
+mod roam;
+mod shop;
+mod split;
+mod steal;
+mod summon;
 mod tooltips;
 mod use_items;
 mod victory;
+mod wander;
 
 use crate::prelude::*;
 
+/// The systems deciding what every monster wants to do this turn
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct MonsterAiSet;
+
+/// The part of `MonsterAiSet` carrying out the actions the monsters chose
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct MonsterActionSet;
+
+/// Resolving this turn's attacks and what happens because of them,
+/// before anybody moves
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct CombatSet;
+
 pub fn build_game_schedule(app: &mut App) {
     app.add_systems(
         (init_game::init_game_system, end_turn::end_turn_system)
@@ -30,7 +68,7 @@ pub fn build_game_schedule(app: &mut App) {
             advance_level::advance_level_system,
             end_turn::end_turn_system,
         )
-            .in_set(OnUpdate(TurnState::NextLevel))
+            .in_set(OnUpdate(TurnState::ChangeLevel))
             .chain(),
     );
 
@@ -42,6 +80,7 @@ pub fn build_game_schedule(app: &mut App) {
 
     app.add_systems(
         (
+            auto_action::auto_action_system,
             player_input::player_input_system,
             apply_system_buffers,
             fov::fov_system,
@@ -61,6 +100,19 @@ pub fn build_game_schedule(app: &mut App) {
             apply_system_buffers,
             combat::combat_system,
             apply_system_buffers,
+            life_drain::life_drain_system,
+            provoke::provoke_system,
+            on_hit::on_hit_system,
+            split::split_system,
+            apply_system_buffers,
+        )
+            .chain()
+            .in_set(CombatSet)
+            .in_set(OnUpdate(TurnState::PlayerTurn)),
+    );
+
+    app.add_systems(
+        (
             movement::movement_system,
             apply_system_buffers,
             fov::fov_system,
@@ -69,20 +121,74 @@ pub fn build_game_schedule(app: &mut App) {
             entity_renderer::entity_renderer_system,
             hud::hud_system,
             tooltips::tooltips_system,
+            regeneration::regeneration_system,
+            hunger::hunger_system,
             end_turn::end_turn_system,
             check_end_of_level::check_end_of_level_system,
         )
             .chain()
+            .after(CombatSet)
             .in_set(OnUpdate(TurnState::PlayerTurn)),
     );
 
     app.add_systems(
         (
             use_items::use_items_system,
+            hear_noise::hear_noise_system,
+            notice::notice_system,
+            apply_system_buffers,
+            behaviour::behaviour_system,
+            apply_system_buffers,
+            boss::boss_system,
+            apply_system_buffers,
+        )
+            .chain()
+            .in_set(MonsterAiSet)
+            .before(MonsterActionSet)
+            .in_set(OnUpdate(TurnState::MonsterTurn)),
+    );
+
+    app.add_systems(
+        (
             chasing::chasing_system,
+            flee::flee_system,
+            keep_distance::keep_distance_system,
+            guard::guard_system,
+            investigate::investigate_system,
+            patrol::patrol_system,
+            wander::wander_system,
+            roam::roam_system,
+            follow::follow_system,
+            breath::breath_system,
+            summon::summon_system,
+            steal::steal_system,
+        )
+            .chain()
+            .in_set(MonsterAiSet)
+            .in_set(MonsterActionSet)
+            .in_set(OnUpdate(TurnState::MonsterTurn)),
+    );
+
+    app.add_systems(
+        (
+            afflictions::afflictions_system,
             apply_system_buffers,
             combat::combat_system,
             apply_system_buffers,
+            life_drain::life_drain_system,
+            provoke::provoke_system,
+            on_hit::on_hit_system,
+            split::split_system,
+            apply_system_buffers,
+        )
+            .chain()
+            .after(MonsterAiSet)
+            .in_set(CombatSet)
+            .in_set(OnUpdate(TurnState::MonsterTurn)),
+    );
+
+    app.add_systems(
+        (
             movement::movement_system,
             apply_system_buffers,
             fov::fov_system,
@@ -95,9 +201,16 @@ pub fn build_game_schedule(app: &mut App) {
             check_end_of_level::check_end_of_level_system,
         )
             .chain()
+            .after(CombatSet)
             .in_set(OnUpdate(TurnState::MonsterTurn)),
     );
 
+    app.add_system(
+        dungeon_overview::dungeon_overview_system.in_set(OnUpdate(TurnState::DungeonOverview)),
+    );
+
+    app.add_system(shop::shop_system.in_set(OnUpdate(TurnState::Shopping)));
+
     app.add_system(game_over::game_over_system.in_set(OnUpdate(TurnState::GameOver)));
 
     app.add_system(victory::victory_system.in_set(OnUpdate(TurnState::Victory)));
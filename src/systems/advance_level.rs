@@ -2,18 +2,44 @@ use crate::prelude::*;
 
 pub fn advance_level_system(
     mut commands: Commands,
-    mut player_query: Query<(Entity, &mut Player)>,
-    other_entities: Query<(Entity, Option<&Carried>), Without<Player>>,
+    map: Res<Map>,
+    theme: Res<MapTheme>,
+    map_info: Res<MapInfo>,
+    mut wander_map: ResMut<WanderMap>,
+    mut level_store: ResMut<LevelStore>,
+    mut player_query: Query<(Entity, &mut Player, &WantsToChangeLevel)>,
+    other_entities: Query<(Entity, &Position), (Without<Player>, Without<Ally>)>,
+    noises: Query<Entity, With<Noise>>,
 ) {
-    let (player_entity, mut player) = player_query.single_mut();
-    player.map_level += 1;
+    let (player_entity, mut player, wants_to_change_level) = player_query.single_mut();
+    let current_level = player.map_level;
 
-    let not_carried = other_entities
-        .iter()
-        .filter(|(_entity, carried)| match carried {
-            Some(Carried(carried_by)) => *carried_by != player_entity,
-            None => true,
-        });
+    level_store.levels.insert(
+        current_level,
+        StoredLevel {
+            map: map.clone(),
+            theme: *theme,
+            map_info: map_info.clone(),
+            wander_map: std::mem::take(&mut *wander_map),
+        },
+    );
+    level_store.previous_level = Some(current_level);
 
-    not_carried.for_each(|(entity, _)| commands.entity(entity).despawn());
+    other_entities.for_each(|(entity, pos)| {
+        commands
+            .entity(entity)
+            .remove::<Position>()
+            .insert(Stashed {
+                map_level: current_level,
+                position: pos.0,
+            });
+    });
+
+    // nobody on the next level hears what happened on this one
+    noises.for_each(|noise| commands.entity(noise).despawn());
+
+    player.map_level = wants_to_change_level.destination;
+    commands
+        .entity(player_entity)
+        .remove::<(WantsToChangeLevel, AutoAction)>();
 }
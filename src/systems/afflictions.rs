@@ -0,0 +1,62 @@
+use crate::prelude::*;
+
+/// Runs the status effects down once a turn, burning whoever is on fire.
+/// Whoever is stashed away on another level is left alone until they're back
+pub fn afflictions_system(
+    mut commands: Commands,
+    mut afflicted: Query<(Entity, &mut Afflictions), With<Position>>,
+) {
+    afflicted.for_each_mut(|(entity, mut afflictions)| {
+        afflictions.tick().into_iter().for_each(|(source, damage)| {
+            commands.spawn(Damages {
+                attacker: source,
+                target: entity,
+                amount: damage,
+            });
+        });
+        if afflictions.0.is_empty() {
+            commands.entity(entity).remove::<Afflictions>();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::afflictions_system;
+
+    #[test]
+    fn test_stashed_afflictions_wait() {
+        let mut world = World::new();
+        let burning = |source| {
+            Afflictions(vec![Affliction {
+                effect: StatusEffect::Burning {
+                    damage: 1,
+                    turns: 1,
+                },
+                source,
+                turns_left: 1,
+            }])
+        };
+        let source = world.spawn_empty().id();
+        let here = world.spawn((Position(Point::zero()), burning(source))).id();
+        let stashed = world
+            .spawn((
+                Stashed {
+                    map_level: 1,
+                    position: Point::zero(),
+                },
+                burning(source),
+            ))
+            .id();
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(afflictions_system);
+        schedule.run(&mut world);
+
+        assert!(world.get::<Afflictions>(here).is_none());
+        assert_eq!(world.get::<Afflictions>(stashed), Some(&burning(source)));
+        assert_eq!(world.query::<&Damages>().iter(&world).count(), 1);
+    }
+}
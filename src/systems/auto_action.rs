@@ -0,0 +1,186 @@
+use crate::prelude::*;
+
+/// Carries on resting, exploring or travelling for the player, until a key
+/// is pressed, they get hurt or something worth looking at comes into view
+pub fn auto_action_system(
+    mut commands: Commands,
+    key: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamedata: Res<GameData>,
+    map: Res<Map>,
+    mut next_state: ResMut<NextState<TurnState>>,
+    mut player: Query<
+        (
+            Entity,
+            &Position,
+            &Health,
+            &FieldOfView,
+            &Faction,
+            &Hunger,
+            &mut AutoAction,
+        ),
+        With<Player>,
+    >,
+    monsters: Query<(Entity, &Position, &Faction), (With<Enemy>, Without<Player>)>,
+    items: Query<(Entity, &Position), (With<Item>, Without<Player>)>,
+) {
+    let Ok((player, pos, health, fov, faction, hunger, mut auto)) = player.get_single_mut() else {
+        return;
+    };
+
+    let hostiles_in_view = monsters
+        .iter()
+        .filter(|(_, monster_pos, monster_faction)| {
+            fov.visible_tiles.contains(&monster_pos.0)
+                && gamedata.is_hostile(&monster_faction.0, &faction.0)
+        })
+        .map(|(monster, _, _)| monster)
+        .collect::<Vec<Entity>>();
+    let visible_items = items
+        .iter()
+        .filter(|(_, item_pos)| fov.visible_tiles.contains(&item_pos.0))
+        .map(|(item, _)| item)
+        .collect::<Vec<Entity>>();
+    let started = auto.health.is_some();
+    let new_item = started && visible_items.iter().any(|i| !auto.in_view.contains(i));
+    let new_hostile = started && hostiles_in_view.iter().any(|m| !auto.in_view.contains(m));
+    let hurt = auto.health.is_some_and(|h| health.current < h);
+
+    let step = match auto.kind {
+        AutoActionKind::Rest => {
+            if health.current >= health.max || hunger.food == 0 {
+                None
+            } else {
+                Some(pos.0)
+            }
+        }
+        AutoActionKind::Explore => {
+            if new_item || auto.position == Some(pos.0) {
+                None
+            } else {
+                explore_step(&map, pos.0, gamedata.max_dijkstra_depth)
+            }
+        }
+        AutoActionKind::Travel(target) => {
+            if target == pos.0 || auto.position == Some(pos.0) {
+                None
+            } else {
+                travel_step(&map, pos.0, target, gamedata.max_dijkstra_depth)
+            }
+        }
+    };
+
+    // travelling only stops for monsters that weren't already in sight
+    let monster_seen = match auto.kind {
+        AutoActionKind::Travel(_) => new_hostile,
+        _ => !hostiles_in_view.is_empty(),
+    };
+    let interrupted = key.get_pressed().next().is_some()
+        || mouse.get_pressed().next().is_some()
+        || monster_seen
+        || hurt;
+    match step {
+        Some(destination) if !interrupted => {
+            auto.health = Some(health.current);
+            auto.position = Some(pos.0);
+            auto.in_view.extend(visible_items);
+            auto.in_view.extend(hostiles_in_view);
+            if destination != pos.0 {
+                commands.entity(player).insert(WantsToMove { destination });
+            }
+            next_state.set(TurnState::PlayerTurn);
+        }
+        _ => {
+            commands.entity(player).remove::<AutoAction>();
+        }
+    }
+}
+
+/// The next step towards the closest tile the player hasn't seen
+fn explore_step(map: &Map, from: Point, max_depth: f32) -> Option<Point> {
+    let map = &without_stairs(map, None);
+    let frontier = (0..map.tiles.len())
+        .filter(|idx| {
+            map.revealed[*idx] != Revealed::Seen && map.can_enter_tile(map.index_to_point(*idx))
+        })
+        .collect::<Vec<usize>>();
+    if frontier.is_empty() {
+        return None;
+    }
+
+    DijkstraMap::new(map.width(), map.height(), &frontier, map, max_depth)
+        .find_lowest_exit(map.point_to_index(from), map)
+        .map(|idx| map.index_to_point(idx))
+}
+
+/// The next step on the way to `target`
+fn travel_step(map: &Map, from: Point, target: Point, max_depth: f32) -> Option<Point> {
+    let map = &without_stairs(map, Some(target));
+    map.uncached_dijkstra_map(target, max_depth)
+        .find_lowest_exit(map.point_to_index(from), map)
+        .map(|idx| map.index_to_point(idx))
+}
+
+/// A copy of `map` with the stairs walled up, apart from `keep`, so that
+/// walking on doesn't take the player to another level by accident
+fn without_stairs(map: &Map, keep: Option<Point>) -> Map {
+    let mut walled = map.clone();
+    (0..map.tiles.len())
+        .map(|idx| map.index_to_point(idx))
+        .filter(|pt| {
+            Some(*pt) != keep && matches!(map.tile_at(*pt), TileType::Exit | TileType::UpStairs)
+        })
+        .for_each(|pt| walled.set_tile(pt, TileType::Wall));
+    walled
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{explore_step, travel_step};
+
+    /// A corridor with the stairs down at x = 2, the player at x = 3 and an
+    /// unseen room behind them at x = 1
+    fn corridor() -> Map {
+        let mut map = Map::new(9, 3, TileType::Wall);
+        map.clear_rect(IRect::with_size(1, 1, 7, 1));
+        map.set_tile(Point::new(2, 1), TileType::Exit);
+        (2..8).for_each(|x| map.reveal(Point::new(x, 1)));
+        map
+    }
+
+    #[test]
+    fn test_explore_step() {
+        let mut map = corridor();
+        map.set_tile(Point::new(2, 1), TileType::Floor);
+        assert_eq!(
+            explore_step(&map, Point::new(5, 1), 100.0),
+            Some(Point::new(4, 1))
+        );
+
+        map.reveal(Point::new(1, 1));
+        assert_eq!(explore_step(&map, Point::new(5, 1), 100.0), None);
+    }
+
+    #[test]
+    fn test_explore_avoids_stairs() {
+        let map = corridor();
+        assert_eq!(explore_step(&map, Point::new(3, 1), 100.0), None);
+    }
+
+    #[test]
+    fn test_travel_avoids_stairs() {
+        let map = corridor();
+        let from = Point::new(3, 1);
+        assert_eq!(travel_step(&map, from, Point::new(1, 1), 100.0), None);
+        assert_eq!(
+            travel_step(&map, from, Point::new(2, 1), 100.0),
+            Some(Point::new(2, 1))
+        );
+        assert_eq!(
+            travel_step(&map, from, Point::new(7, 1), 100.0),
+            Some(Point::new(4, 1))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::prelude::*;
+
+pub fn behaviour_system(
+    mut commands: Commands,
+    gamedata: Res<GameData>,
+    player: Query<(Entity, &Position), With<Player>>,
+    carried: Query<&Carried, With<Item>>,
+    creatures: Query<(Entity, &Position, &Faction), With<Health>>,
+    mut monsters: Query<
+        (
+            Entity,
+            &Behaviour,
+            &Position,
+            &FieldOfView,
+            &Health,
+            &Post,
+            &Faction,
+            Option<&LastSeenPlayer>,
+            Option<&FleesBelow>,
+            Option<&mut Abilities>,
+        ),
+        Without<Asleep>,
+    >,
+) {
+    if let Ok((player, player_pos)) = player.get_single() {
+        let player_has_items = carried.iter().any(|carried| carried.0 == player);
+        monsters.for_each_mut(
+            |(
+                entity,
+                behaviour,
+                pos,
+                fov,
+                health,
+                post,
+                faction,
+                memory,
+                flees_below,
+                abilities,
+            )| {
+                let sees_player = fov.visible_tiles.contains(&player_pos.0);
+
+                // the enemy is the closest visible member of a faction this one hates
+                let enemy = creatures
+                    .iter()
+                    .filter(|(_, enemy_pos, enemy_faction)| {
+                        fov.visible_tiles.contains(&enemy_pos.0)
+                            && gamedata.is_hostile(&faction.0, &enemy_faction.0)
+                    })
+                    .min_by_key(|(_, enemy_pos, _)| pos.0.pythagorean_squared_distance(enemy_pos.0))
+                    .map(|(enemy, enemy_pos, _)| (enemy, enemy_pos.0));
+
+                let situation = AiSituation {
+                    sees_enemy: enemy.is_some(),
+                    remembers_player: sees_player || memory.is_some(),
+                    health_fraction: health.current as f32 / health.max as f32,
+                    enemy_distance: enemy.map_or(f32::MAX, |(_, enemy_pos)| {
+                        pos.0.pythagorean_distance(enemy_pos)
+                    }),
+                    enemy_distance_from_post: enemy.map_or(f32::MAX, |(_, enemy_pos)| {
+                        post.0.pythagorean_distance(enemy_pos)
+                    }),
+                    can_steal: player_has_items
+                        && enemy.is_some_and(|(enemy, enemy_pos)| {
+                            enemy == player && within_steal_reach(pos.0, enemy_pos)
+                        }),
+                };
+
+                let mut monster = commands.entity(entity);
+                if sees_player {
+                    monster.insert(LastSeenPlayer {
+                        position: player_pos.0,
+                        turns_searching: 0,
+                    });
+                }
+                match enemy {
+                    Some((enemy, _)) => monster.insert(Target(enemy)),
+                    None => monster.remove::<Target>(),
+                };
+                monster.remove::<ActionMarkers>();
+
+                // a ready ability whose trigger holds is used instead of acting normally
+                if let Some(mut abilities) = abilities {
+                    abilities
+                        .0
+                        .iter_mut()
+                        .for_each(|a| a.cooldown = a.cooldown.saturating_sub(1));
+                    let ready = abilities
+                        .0
+                        .iter_mut()
+                        .find(|a| a.cooldown == 0 && a.template.ready(&situation));
+                    if let Some(ready) = ready {
+                        ready.cooldown = ready.template.cooldown + 1;
+                        match &ready.template.ability {
+                            Ability::Breath { range, damage } => monster.insert(Breathing {
+                                range: *range,
+                                damage: *damage,
+                            }),
+                            Ability::Summon { template, count } => monster.insert(Summoning {
+                                template: template.clone(),
+                                count: *count,
+                            }),
+                            Ability::Steal { behaviour } => monster.insert(Stealing {
+                                behaviour: behaviour.clone(),
+                            }),
+                            Ability::Split | Ability::LifeDrain(_) => &mut monster,
+                        };
+                        return;
+                    }
+                }
+
+                let action = match flees_below {
+                    Some(fraction)
+                        if situation.sees_enemy && situation.health_fraction < fraction.0 =>
+                    {
+                        AiAction::Flee
+                    }
+                    _ => behaviour.0.choose(&situation).unwrap_or(AiAction::Wait),
+                };
+                match action {
+                    AiAction::Chase => monster.insert(Chasing),
+                    AiAction::Flee => monster.insert(Fleeing),
+                    AiAction::KeepDistance(distance) => monster.insert(KeepingDistance(distance)),
+                    AiAction::ReturnToPost => monster.insert(ReturningToPost),
+                    AiAction::Investigate(search_turns) => {
+                        monster.insert(Investigating { search_turns })
+                    }
+                    AiAction::Patrol => monster.insert(Patrolling),
+                    AiAction::Wander => monster.insert(Wandering),
+                    AiAction::Roam => monster.insert(Roaming),
+                    AiAction::Follow(distance) => monster.insert(Following(distance)),
+                    AiAction::Wait => &mut monster,
+                };
+            },
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+/// Moves bosses into the phase matching their health, and lets bosses with
+/// an enemy use their next ability instead of acting normally
+pub fn boss_system(
+    mut commands: Commands,
+    gamedata: Res<GameData>,
+    mut rng: ResMut<GameRng>,
+    map: Res<Map>,
+    mut bosses: Query<
+        (
+            Entity,
+            &mut Boss,
+            &mut Health,
+            &Position,
+            &Faction,
+            Option<&Target>,
+        ),
+        Without<Asleep>,
+    >,
+    creatures: Query<(Entity, &Position, &Faction), With<Health>>,
+    positions: Query<&Position>,
+) {
+    bosses.for_each_mut(|(entity, mut boss, mut health, pos, faction, target)| {
+        let phase = BossPhase::current(&boss.phases, health.current as f32 / health.max as f32);
+        if phase != boss.phase {
+            boss.phase = phase;
+            boss.next_ability = 0;
+            if let Some(behaviour) = &boss.phases[phase].behaviour {
+                commands
+                    .entity(entity)
+                    .insert(Behaviour(gamedata.behaviour_named(behaviour).tree.clone()));
+            }
+        }
+
+        if target.is_none() {
+            return;
+        }
+        if boss.cooldown > 0 {
+            boss.cooldown -= 1;
+            return;
+        }
+        let current = &boss.phases[boss.phase];
+        if current.abilities.is_empty() {
+            return;
+        }
+        let ability = current.abilities[boss.next_ability % current.abilities.len()].clone();
+        boss.cooldown = current.cooldown;
+        boss.next_ability += 1;
+
+        match ability {
+            BossAbility::Slam { radius, damage } => {
+                creatures
+                    .iter()
+                    .filter(|(_, victim_pos, victim_faction)| {
+                        pos.0.pythagorean_distance(victim_pos.0) <= radius
+                            && gamedata.is_hostile(&faction.0, &victim_faction.0)
+                    })
+                    .for_each(|(victim, _, _)| {
+                        commands.spawn(Damages {
+                            attacker: entity,
+                            target: victim,
+                            amount: damage,
+                        });
+                    });
+            }
+            BossAbility::SummonMinions { template, count } => {
+                let mut occupied = positions.iter().map(|p| p.0).collect::<HashSet<Point>>();
+                group_points(&map, pos.0, count, &mut occupied)
+                    .into_iter()
+                    .for_each(|pt| {
+                        gamedata.spawn_summoned(&mut commands, &mut rng.0, pt, &template);
+                    });
+            }
+            BossAbility::Heal(amount) => {
+                health.current = i32::min(health.max, health.current + amount);
+            }
+        }
+
+        // using an ability takes the boss's whole turn
+        commands.entity(entity).remove::<ActionMarkers>();
+    });
+}
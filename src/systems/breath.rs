@@ -0,0 +1,35 @@
+use crate::prelude::*;
+
+/// How far off the line to the target a breath reaches, in degrees
+const BREATH_HALF_ANGLE: f32 = 30.0;
+
+pub fn breath_system(
+    mut commands: Commands,
+    breathers: Query<(Entity, &Position, &FieldOfView, &Breathing, &Target)>,
+    creatures: Query<(Entity, &Position), With<Health>>,
+) {
+    breathers
+        .iter()
+        .for_each(|(entity, pos, fov, breathing, target)| {
+            let Ok((_, target_pos)) = creatures.get(target.0) else {
+                return;
+            };
+            let cone = breath_cone(pos.0, target_pos.0, breathing.range, BREATH_HALF_ANGLE);
+
+            // the breath hits friend and foe alike, but not through walls
+            creatures
+                .iter()
+                .filter(|(victim, victim_pos)| {
+                    *victim != entity
+                        && cone.contains(&victim_pos.0)
+                        && fov.visible_tiles.contains(&victim_pos.0)
+                })
+                .for_each(|(victim, _)| {
+                    commands.spawn(Damages {
+                        attacker: entity,
+                        target: victim,
+                        amount: breathing.damage,
+                    });
+                });
+        });
+}
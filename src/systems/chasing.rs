@@ -4,44 +4,27 @@ pub fn chasing_system(
     mut map: ResMut<Map>,
     gamedata: Res<GameData>,
     mut commands: Commands,
-    movers: Query<(Entity, &Position, &ChasingPlayer, &FieldOfView)>,
-    positions: Query<(Entity, &Position, &Health, Option<&Player>)>,
-    player: Query<&Position, With<Player>>,
+    movers: Query<(Entity, &Position, &Target), With<Chasing>>,
+    positions: Query<&Position>,
 ) {
-    if let Ok(player_pos) = player.get_single() {
-        let dijkstra_map = map.dijkstra_map(player_pos.0, gamedata.max_monster_visibility);
+    let mut maps = TargetMaps::default();
+    movers.iter().for_each(|(entity, pos, target)| {
+        let Ok(target_pos) = positions.get(target.0) else {
+            return;
+        };
+        let dijkstra_map = maps.get(&mut map, target_pos.0, gamedata.max_monster_visibility);
 
-        movers.iter().for_each(|(entity, pos, _, fov)| {
-            if !fov.visible_tiles.contains(&player_pos.0) {
-                return;
-            }
-            let idx = map.point_to_index(pos.0);
-            if let Some(destination) = dijkstra_map.find_lowest_exit(idx, map.as_ref()) {
-                let distance = pos.0.pythagorean_distance(player_pos.0);
-                let destination = if distance > 1.2 {
-                    map.index_to_point(destination)
-                } else {
-                    player_pos.0
-                };
-
-                let mut attacked = false;
-                positions
-                    .iter()
-                    .filter(|(_, target_pos, _, _)| target_pos.0 == destination)
-                    .for_each(|(victim, _, _, optional_player)| {
-                        if optional_player.is_some() {
-                            commands.spawn(WantsToAttack {
-                                attacker: entity,
-                                target: victim,
-                            });
-                            attacked = true;
-                        }
-                    });
-
-                if !attacked {
-                    commands.entity(entity).insert(WantsToMove { destination });
-                }
-            }
-        });
-    }
+        if pos.0.pythagorean_distance(target_pos.0) < 1.2 {
+            commands.spawn(WantsToAttack {
+                attacker: entity,
+                target: target.0,
+            });
+        } else if let Some(destination) =
+            dijkstra_map.find_lowest_exit(map.point_to_index(pos.0), map.as_ref())
+        {
+            commands.entity(entity).insert(WantsToMove {
+                destination: map.index_to_point(destination),
+            });
+        }
+    });
 }
@@ -1,16 +1,20 @@
 use crate::prelude::*;
 
 pub fn check_end_of_level_system(
+    mut commands: Commands,
     mut next_state: ResMut<NextState<TurnState>>,
-    map: Res<Map>,
-    player: Query<(&Health, &Position), With<Player>>,
+    player: Query<(Entity, &Health, &Position), With<Player>>,
+    stairs: Query<(&Position, &Stairs)>,
     amulet: Query<&Position, With<AmuletOfYala>>,
 ) {
-    player.iter().for_each(|(hp, player_pos)| {
+    player.iter().for_each(|(player_entity, hp, player_pos)| {
         if hp.current < 1 {
             next_state.set(TurnState::GameOver)
-        } else if map.tile_at(player_pos.0) == TileType::Exit {
-            next_state.set(TurnState::NextLevel);
+        } else if let Some((_, stairs)) = stairs.iter().find(|(pos, _)| *pos == player_pos) {
+            commands.entity(player_entity).insert(WantsToChangeLevel {
+                destination: stairs.destination,
+            });
+            next_state.set(TurnState::ChangeLevel);
         } else {
             amulet.iter().for_each(|amulet_pos| {
                 if *player_pos == *amulet_pos {
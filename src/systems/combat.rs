@@ -1,49 +1,117 @@
 use std::cmp::max;
+use std::collections::HashSet;
 
 use crate::prelude::*;
 
 pub fn combat_system(
+    gamedata: Res<GameData>,
+    mut rng: ResMut<GameRng>,
     mut commands: Commands,
     attacks: Query<(Entity, &WantsToAttack)>,
-    attackers: Query<(Entity, &Damage)>,
-    targets: Query<(Entity, &Health, Option<&Player>)>,
+    special_damage: Query<(Entity, &Damages)>,
+    attackers: Query<&Damage>,
+    mut targets: Query<(&mut Health, Option<&Player>, &DCName, Option<&Summoned>)>,
     weapons: Query<(&Carried, &Damage, &Weapon)>,
+    positions: Query<&Position>,
+    factions: Query<&Faction>,
+    carried: Query<(Entity, &Carried)>,
+    afflictions: Query<&Afflictions>,
+    hunger: Query<&Hunger>,
 ) {
+    let mut hits = Vec::new();
     attacks.iter().for_each(|(message, wants_to_attack)| {
-        let attacker = wants_to_attack.attacker;
-        let target = wants_to_attack.target;
-
-        let (target_health, target_is_player) = targets
-            .iter()
-            .filter(|t| t.0 == target)
-            .map(|(_, h, p)| (h, p.is_some()))
-            .next()
-            .unwrap();
-
-        let attacker_base_damage = attackers
-            .iter()
-            .filter(|a| a.0 == attacker)
-            .map(|(_, dmg)| dmg.0)
-            .next()
-            .unwrap();
+        commands.entity(message).despawn();
 
+        let attacker = wants_to_attack.attacker;
+        let Ok(attacker_base_damage) = attackers.get(attacker) else {
+            return;
+        };
         let weapon_damage: i32 = weapons
             .iter()
-            .filter(|(carried, _, weapon)| carried.0 == wants_to_attack.attacker && weapon.equipped)
+            .filter(|(carried, _, weapon)| carried.0 == attacker && weapon.equipped)
             .map(|(_, dmg, _)| dmg.0)
             .sum();
+        let weakened: i32 = afflictions
+            .get(attacker)
+            .map(|a| {
+                a.0.iter()
+                    .map(|a| match a.effect {
+                        StatusEffect::Weakened { amount, .. } => amount,
+                        _ => 0,
+                    })
+                    .sum()
+            })
+            .unwrap_or(0);
 
-        let final_damage = attacker_base_damage + weapon_damage;
-
-        let new_health = max(0, target_health.current - final_damage);
-        commands.entity(target).insert(Health {
-            current: new_health,
-            max: target_health.max,
-        });
-        if new_health <= 0 && !target_is_player {
-            commands.entity(target).despawn();
-        }
+        let hunger_damage = hunger
+            .get(attacker)
+            .map_or(0, |hunger| gamedata.hunger_state(hunger).damage);
 
+        hits.push((
+            attacker,
+            wants_to_attack.target,
+            max(
+                0,
+                attacker_base_damage.0 + weapon_damage - weakened + hunger_damage,
+            ),
+            true,
+        ));
+    });
+    special_damage.iter().for_each(|(message, damages)| {
         commands.entity(message).despawn();
+        hits.push((damages.attacker, damages.target, damages.amount, false));
     });
+
+    // anybody killed earlier this turn neither fights back nor gets hit again
+    let mut dead = HashSet::new();
+
+    hits.into_iter()
+        .for_each(|(attacker, target, damage, melee)| {
+            if dead.contains(&attacker) || dead.contains(&target) {
+                return;
+            }
+            let Ok((mut target_health, target_player, target_name, summoned)) =
+                targets.get_mut(target)
+            else {
+                return;
+            };
+
+            target_health.current = max(0, target_health.current - damage);
+            commands.spawn(Hit {
+                attacker,
+                target,
+                amount: damage,
+                melee,
+                target_faction: factions.get(target).ok().map(|f| f.0.clone()),
+                target_position: positions.get(target).ok().map(|p| p.0),
+            });
+            if target_health.current <= 0 && target_player.is_none() {
+                dead.insert(target);
+                commands.entity(target).despawn();
+
+                // the loot, and anything the monster was carrying, drops where it died.
+                // Summoned monsters only drop what they were carrying
+                if let Ok(pos) = positions.get(target) {
+                    if summoned.is_none() {
+                        gamedata.drop_loot(&mut commands, &mut rng.0, &target_name.0, pos.0);
+                    }
+                    carried
+                        .iter()
+                        .filter(|(_, carried)| carried.0 == target)
+                        .for_each(|(item, _)| {
+                            commands
+                                .entity(item)
+                                .remove::<Carried>()
+                                .insert(Position(pos.0));
+                        });
+                }
+            }
+
+            if let Ok(pos) = positions.get(target) {
+                commands.spawn(Noise {
+                    origin: pos.0,
+                    loudness: gamedata.noise.fighting,
+                });
+            }
+        });
 }
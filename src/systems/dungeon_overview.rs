@@ -0,0 +1,100 @@
+use crate::prelude::*;
+
+pub fn dungeon_overview_system(
+    mut next_state: ResMut<NextState<TurnState>>,
+    key_press: Res<Input<KeyCode>>,
+    gamedata: Res<GameData>,
+    level_store: Res<LevelStore>,
+    knowledge: Res<ItemKnowledge>,
+    run_seed: Res<RunSeed>,
+    player: Query<&Player>,
+) {
+    let current_level = player.single().map_level;
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    draw_batch.print_color_centered(2, "The Dungeon So Far", ColorPair::new(YELLOW, BLACK));
+    draw_batch.print_color_right(
+        Point::new(gamedata.text_display_width(), 0).to_bracket_point(),
+        format!("Run {}", run_seed.0),
+        ColorPair::new(GREY, BLACK),
+    );
+
+    let mut branches: Vec<&String> = Vec::new();
+    gamedata.game_levels.iter().for_each(|level| {
+        if !branches.contains(&&level.branch) {
+            branches.push(&level.branch);
+        }
+    });
+
+    let mut y = 4;
+    branches.iter().for_each(|branch| {
+        let mut visited = gamedata
+            .game_levels
+            .iter()
+            .enumerate()
+            .filter(|(idx, level)| {
+                level.branch == **branch
+                    && (*idx == current_level || level_store.levels.contains_key(idx))
+            })
+            .collect::<Vec<(usize, &GameLevel)>>();
+        if visited.is_empty() {
+            return;
+        }
+        visited.sort_by_key(|(_, level)| level.depth);
+
+        draw_batch.print_color(
+            Point::new(4, y).to_bracket_point(),
+            *branch,
+            ColorPair::new(GREEN, BLACK),
+        );
+        y += 1;
+        visited.iter().for_each(|(idx, level)| {
+            let (marker, color) = if *idx == current_level {
+                ("@", ColorPair::new(YELLOW, BLACK))
+            } else {
+                (" ", ColorPair::new(WHITE, BLACK))
+            };
+            draw_batch.print_color(
+                Point::new(6, y).to_bracket_point(),
+                format!("{marker} depth {:>2} : {}", level.depth, level.name),
+                color,
+            );
+            y += 1;
+        });
+        y += 1;
+    });
+
+    // what the player has found out about the items they've come across
+    let x = gamedata.text_display_width() / 2;
+    let mut known_y = 4;
+    draw_batch.print_color(
+        Point::new(x, known_y).to_bracket_point(),
+        "Identified Items",
+        ColorPair::new(GREEN, BLACK),
+    );
+    known_y += 1;
+    knowledge
+        .known_items()
+        .iter()
+        .for_each(|(appearance, name)| {
+            draw_batch.print_color(
+                Point::new(x + 2, known_y).to_bracket_point(),
+                format!("{appearance} : {name}"),
+                ColorPair::new(WHITE, BLACK),
+            );
+            known_y += 1;
+        });
+    let y = y.max(known_y + 1);
+
+    draw_batch.print_color_centered(
+        y + 1,
+        "Press Escape to return.",
+        ColorPair::new(GREEN, BLACK),
+    );
+    draw_batch.submit(10000).expect("Batch error");
+
+    if key_press.any_pressed([KeyCode::Escape, KeyCode::Tab]) {
+        next_state.set(TurnState::AwaitingInput);
+    }
+}
@@ -6,11 +6,13 @@ pub fn end_turn_system(
 ) {
     let new_state = match current_state.0 {
         TurnState::InitGame => Some(TurnState::InitLevel),
-        TurnState::NextLevel => Some(TurnState::InitLevel),
+        TurnState::ChangeLevel => Some(TurnState::InitLevel),
         TurnState::InitLevel => Some(TurnState::AwaitingInput),
         TurnState::AwaitingInput => None,
         TurnState::PlayerTurn => Some(TurnState::MonsterTurn),
         TurnState::MonsterTurn => Some(TurnState::AwaitingInput),
+        TurnState::DungeonOverview => None,
+        TurnState::Shopping => None,
         TurnState::GameOver => None,
         TurnState::Victory => None,
     };
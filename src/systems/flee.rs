@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+/// How strongly fleeing monsters prefer getting far away over the nearest escape
+const FLEE_FACTOR: f32 = -1.2;
+
+pub fn flee_system(
+    mut map: ResMut<Map>,
+    gamedata: Res<GameData>,
+    mut commands: Commands,
+    movers: Query<(Entity, &Position, &Target), With<Fleeing>>,
+    positions: Query<&Position>,
+) {
+    // everybody fleeing from the same spot shares a map
+    let mut flee_maps = HashMap::new();
+    movers.iter().for_each(|(entity, pos, target)| {
+        let Ok(target_pos) = positions.get(target.0) else {
+            return;
+        };
+        let flee_map = flee_maps.entry(target_pos.0).or_insert_with(|| {
+            map.dijkstra_map(target_pos.0, gamedata.max_monster_visibility)
+                .inverted(FLEE_FACTOR, map.as_ref())
+        });
+
+        let idx = map.point_to_index(pos.0);
+        if let Some(destination) = flee_map.find_lowest_exit(idx, map.as_ref()) {
+            commands.entity(entity).insert(WantsToMove {
+                destination: map.index_to_point(destination),
+            });
+        }
+    });
+}
@@ -0,0 +1,27 @@
+use crate::prelude::*;
+
+pub fn follow_system(
+    mut map: ResMut<Map>,
+    gamedata: Res<GameData>,
+    mut commands: Commands,
+    movers: Query<(Entity, &Position, &Following)>,
+    player: Query<&Position, With<Player>>,
+) {
+    if let Ok(player_pos) = player.get_single() {
+        let dijkstra_map = map.dijkstra_map(player_pos.0, gamedata.max_dijkstra_depth);
+
+        movers.iter().for_each(|(entity, pos, following)| {
+            if pos.0.pythagorean_distance(player_pos.0) <= following.0 {
+                return;
+            }
+            if let Some(destination) =
+                dijkstra_map.find_lowest_exit(map.point_to_index(pos.0), map.as_ref())
+            {
+                let destination = map.index_to_point(destination);
+                if destination != player_pos.0 {
+                    commands.entity(entity).insert(WantsToMove { destination });
+                }
+            }
+        });
+    }
+}
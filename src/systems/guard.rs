@@ -0,0 +1,19 @@
+use crate::prelude::*;
+
+pub fn guard_system(
+    map: Res<Map>,
+    gamedata: Res<GameData>,
+    mut wander_map: ResMut<WanderMap>,
+    mut commands: Commands,
+    movers: Query<(Entity, &Position, &Post), With<ReturningToPost>>,
+) {
+    movers.iter().for_each(|(entity, pos, post)| {
+        if pos.0 != post.0 {
+            if let Some(destination) =
+                wander_map.step_towards(&map, post.0, pos.0, gamedata.max_dijkstra_depth)
+            {
+                commands.entity(entity).insert(WantsToMove { destination });
+            }
+        }
+    });
+}
@@ -0,0 +1,41 @@
+use crate::prelude::*;
+
+/// Monsters that hear a noise wake up and go to investigate it, as if they
+/// had seen the player there
+pub fn hear_noise_system(
+    map: Res<Map>,
+    mut commands: Commands,
+    noises: Query<(Entity, &Noise)>,
+    listeners: Query<(Entity, &Position, &Hearing)>,
+) {
+    noises.iter().for_each(|(message, noise)| {
+        let max_depth = listeners
+            .iter()
+            .map(|(_, _, hearing)| noise.loudness * hearing.0)
+            .fold(0.0, f32::max);
+        let sound = DijkstraMap::new(
+            map.width(),
+            map.height(),
+            &[map.point_to_index(noise.origin)],
+            &map,
+            max_depth,
+        );
+
+        listeners
+            .iter()
+            .filter(|(_, pos, hearing)| {
+                sound.map[map.point_to_index(pos.0)] <= noise.loudness * hearing.0
+            })
+            .for_each(|(entity, _, _)| {
+                commands
+                    .entity(entity)
+                    .remove::<Asleep>()
+                    .insert(LastSeenPlayer {
+                        position: noise.origin,
+                        turns_searching: 0,
+                    });
+            });
+
+        commands.entity(message).despawn();
+    });
+}
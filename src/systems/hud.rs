@@ -2,17 +2,25 @@ use linked_hash_map::LinkedHashMap;
 
 use crate::prelude::*;
 
+const ALLY_PANEL_WIDTH: i32 = 20;
+
 pub fn hud_system(
     gamedata: Res<GameData>,
     map_info: Res<MapInfo>,
-    player_query: Query<(Entity, &Health), With<Player>>,
-    item_query: Query<(&DCName, &Carried, Option<&Weapon>), (With<Item>, Without<Player>)>,
+    knowledge: Res<ItemKnowledge>,
+    player_query: Query<(Entity, &Health, &FieldOfView, &Purse, &Hunger), With<Player>>,
+    item_query: Query<(&DCName, &Carried, &Render, Option<&Weapon>), (With<Item>, Without<Player>)>,
+    ally_query: Query<(&DCName, &Health), With<Ally>>,
+    boss_query: Query<(&DCName, &Health, &Position), With<Boss>>,
 ) {
-    let (player_entity, player_health) = player_query.single();
+    let (player_entity, player_health, player_fov, purse, hunger) = player_query.single();
 
     let mut draw_batch = DrawBatch::new();
     draw_batch.target(2);
-    draw_batch.print_centered(1, "Explore the Dungeon. Cursor keys to move.");
+    draw_batch.print_centered(
+        1,
+        "Cursor keys: move, R: rest, X: explore, E or click: travel, Tab: overview.",
+    );
     draw_batch.bar_horizontal(
         Point::zero().to_bracket_point(),
         gamedata.text_display_width(),
@@ -29,19 +37,34 @@ pub fn hud_system(
         ColorPair::new(WHITE, RED),
     );
 
+    draw_batch.print_color(
+        Point::new(1, 1).to_bracket_point(),
+        format!("Gold: {}", purse.0),
+        ColorPair::new(GOLD, BLACK),
+    );
+    let hunger_state = gamedata.hunger_state(hunger);
+    if hunger_state.visible {
+        let (r, g, b) = hunger_state.colour;
+        draw_batch.print_color(
+            Point::new(14, 1).to_bracket_point(),
+            &hunger_state.name,
+            ColorPair::new(RGB::from_u8(r, g, b), BLACK),
+        );
+    }
+
     draw_batch.print_color_right(
         Point::new(gamedata.text_display_width(), 1).to_bracket_point(),
-        &map_info.name,
+        format!("{} (depth {})", map_info.name, map_info.depth),
         ColorPair::new(YELLOW, BLACK),
     );
 
     let mut y = 3;
-    let mut item_map: LinkedHashMap<&String, (i32, bool)> = LinkedHashMap::new();
+    let mut item_map: LinkedHashMap<&String, (i32, bool, ColorPair)> = LinkedHashMap::new();
 
     item_query
         .iter()
-        .filter(|(_, carried, _)| carried.0 == player_entity)
-        .for_each(|(name, _, optional_weapon)| {
+        .filter(|(_, carried, _, _)| carried.0 == player_entity)
+        .for_each(|(name, _, render, optional_weapon)| {
             let name = &name.0;
 
             let weapon_equipped = if let Some(weapon) = optional_weapon {
@@ -50,25 +73,28 @@ pub fn hud_system(
                 false
             };
             if item_map.contains_key(name) {
-                let (count, equipped) = item_map.get_mut(name).unwrap();
+                let (count, equipped, _) = item_map.get_mut(name).unwrap();
                 *count += 1;
                 *equipped = *equipped || weapon_equipped;
             } else {
-                item_map.insert(name, (1, weapon_equipped));
+                item_map.insert(name, (1, weapon_equipped, render.color));
             }
         });
 
-    item_map.iter().for_each(|(name, (count, equipped))| {
-        let equip_message = if *equipped { " (E)" } else { "" };
+    item_map
+        .iter()
+        .for_each(|(name, (count, equipped, color))| {
+            let equip_message = if *equipped { " (E)" } else { "" };
+            let name = knowledge.display_name(name);
 
-        let message = if *count > 1 {
-            format!("{} : {} ({}){}", y - 2, name, count, equip_message)
-        } else {
-            format!("{} : {}{}", y - 2, name, equip_message)
-        };
-        draw_batch.print(Point::new(3, y).to_bracket_point(), message);
-        y += 1;
-    });
+            let message = if *count > 1 {
+                format!("{} : {} ({}){}", y - 2, name, count, equip_message)
+            } else {
+                format!("{} : {}{}", y - 2, name, equip_message)
+            };
+            draw_batch.print_color(Point::new(3, y).to_bracket_point(), message, *color);
+            y += 1;
+        });
 
     if y > 3 {
         draw_batch.print_color(
@@ -78,5 +104,51 @@ pub fn hud_system(
         );
     }
 
+    // allies are listed down the right hand side, each with a health bar
+    let panel_x = gamedata.text_display_width() - ALLY_PANEL_WIDTH - 1;
+    let mut y = 3;
+    ally_query.iter().for_each(|(name, health)| {
+        draw_batch.bar_horizontal(
+            Point::new(panel_x, y).to_bracket_point(),
+            ALLY_PANEL_WIDTH,
+            health.current,
+            health.max,
+            ColorPair::new(GREEN, BLACK),
+        );
+        draw_batch.print_color(
+            Point::new(panel_x, y).to_bracket_point(),
+            format!("{} {}/{}", name.0, health.current, health.max),
+            ColorPair::new(WHITE, BLACK),
+        );
+        y += 1;
+    });
+    if y > 3 {
+        draw_batch.print_color(
+            Point::new(panel_x, 2).to_bracket_point(),
+            "Allies",
+            ColorPair::new(YELLOW, BLACK),
+        );
+    }
+
+    // a boss in sight gets a health bar along the bottom of the screen
+    if let Some((name, health, _)) = boss_query
+        .iter()
+        .find(|(_, _, pos)| player_fov.visible_tiles.contains(&pos.0))
+    {
+        let y = gamedata.text_display_height() - 1;
+        draw_batch.bar_horizontal(
+            Point::new(0, y).to_bracket_point(),
+            gamedata.text_display_width(),
+            health.current,
+            health.max,
+            ColorPair::new(PURPLE, BLACK),
+        );
+        draw_batch.print_color_centered(
+            y,
+            format!(" {}: {} / {} ", name.0, health.current, health.max),
+            ColorPair::new(WHITE, PURPLE),
+        );
+    }
+
     draw_batch.submit(10000).expect("Batch error");
 }
@@ -0,0 +1,15 @@
+use crate::prelude::*;
+
+/// Every turn the player gets hungrier, and starving players lose health
+pub fn hunger_system(
+    gamedata: Res<GameData>,
+    mut player: Query<(&mut Hunger, &mut Health), With<Player>>,
+) {
+    if let Ok((mut hunger, mut health)) = player.get_single_mut() {
+        if hunger.food > 0 {
+            hunger.food -= 1;
+        } else {
+            health.current = (health.current - gamedata.starvation_damage).max(0);
+        }
+    }
+}
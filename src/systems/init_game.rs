@@ -2,7 +2,16 @@ use crate::{prelude::*, GAME_DATA_PATH};
 
 pub fn init_game_system(mut commands: Commands, entities: Query<Entity>) {
     let gamedata = GameData::load(GAME_DATA_PATH);
+    let seed = rand::random();
+    commands.insert_resource(RunSeed(seed));
+    commands.insert_resource(GameRng(StdRng::seed_from_u64(seed)));
+    commands.insert_resource(ItemKnowledge::new(
+        seed,
+        &gamedata.appearances,
+        &gamedata.entity_templates,
+    ));
     commands.insert_resource(gamedata);
+    commands.insert_resource(LevelStore::default());
 
     for entity in entities.iter() {
         commands.entity(entity).despawn();
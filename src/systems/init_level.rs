@@ -1,76 +1,191 @@
+use std::collections::HashSet;
+
 use crate::prelude::*;
 
 pub fn init_level_system(
     mut commands: Commands,
     gamedata: Res<GameData>,
+    run_seed: Res<RunSeed>,
+    mut game_rng: ResMut<GameRng>,
+    mut level_store: ResMut<LevelStore>,
     mut player_opt: Query<(&Player, &mut Position, &mut FieldOfView)>,
+    mut stashed: Query<
+        (Entity, &Stashed, Option<&Stairs>, Option<&mut FieldOfView>),
+        Without<Player>,
+    >,
+    mut allies: Query<
+        (Entity, Option<&mut FieldOfView>),
+        (With<Ally>, Without<Stashed>, Without<Player>),
+    >,
 ) {
     let map_level = if let Ok((player, _, _)) = player_opt.get_single() {
         player.map_level
     } else {
         0
     };
+    let previous_level = level_store.previous_level;
+    // where the player's allies must not be put
+    let mut occupied = HashSet::new();
 
-    let mut rng = rand::thread_rng();
+    let (map, theme, map_info, wander_map, arrival) = match level_store.levels.remove(&map_level) {
+        Some(StoredLevel {
+            map,
+            theme,
+            map_info,
+            wander_map,
+        }) => {
+            // arrive on the stairs that lead back where the player came from
+            let arrival = stashed
+                .iter()
+                .filter(|(_, stashed, stairs, _)| {
+                    stashed.map_level == map_level
+                        && stairs.is_some_and(|s| Some(s.destination) == previous_level)
+                })
+                .map(|(_, stashed, _, _)| stashed.position)
+                .next()
+                .unwrap_or_else(|| map.closest_floor_point(map.center()));
 
-    let map_level_def = &gamedata.game_levels[map_level];
+            stashed
+                .iter_mut()
+                .filter(|(_, stashed, _, _)| stashed.map_level == map_level)
+                .for_each(|(entity, stashed, _, optional_fov)| {
+                    occupied.insert(stashed.position);
+                    commands
+                        .entity(entity)
+                        .remove::<Stashed>()
+                        .insert(Position(stashed.position));
+                    if let Some(mut fov) = optional_fov {
+                        fov.is_dirty = true;
+                    }
+                });
 
-    let map_builder = map_level_def.get_builder(&gamedata);
+            (map, theme, map_info, wander_map, arrival)
+        }
+        None => {
+            let mut rng = run_seed.level_rng(map_level);
 
-    let BuiltMap {
-        mut map,
-        mut entity_spawns,
-        player_start,
-        amulet_start,
-    } = map_builder.build(
-        &mut rng,
-        gamedata.map_width,
-        gamedata.map_height,
-        gamedata.num_monsters,
-        gamedata.max_dijkstra_depth,
-    );
+            let map_level_def = &gamedata.game_levels[map_level];
 
-    gamedata.apply_prefab(
-        &mut map,
-        &mut rng,
-        player_start,
-        amulet_start,
-        &mut entity_spawns,
-        gamedata.max_dijkstra_depth,
-    );
+            let BuiltMap {
+                mut map,
+                entity_spawns,
+                named_spawns,
+                rooms,
+                spawn_limits,
+                player_start,
+                amulet_start,
+            } = gamedata.build_level(map_level_def, &mut rng, &mut MapGenHistory::default());
+
+            if let Some(previous_level) = previous_level {
+                map.set_tile(player_start, TileType::UpStairs);
+                gamedata.spawn_stairs(&mut commands, player_start, previous_level);
+            }
+
+            if map_level_def.has_amulet {
+                gamedata.spawn_amulet_of_yala(&mut commands, amulet_start);
+            }
+
+            occupied.extend(entity_spawns.iter().map(|(pt, _)| *pt));
+            occupied.extend(named_spawns.iter().map(|(pt, _)| *pt));
+
+            // the first exit goes where the builder wanted it, any others are
+            // spread out across the far side of the map
+            let mut placed = vec![player_start, amulet_start];
+            map_level_def
+                .exits
+                .iter()
+                .enumerate()
+                .for_each(|(n, exit)| {
+                    let exit_pos = if n == 0 && !map_level_def.has_amulet {
+                        amulet_start
+                    } else {
+                        map.random_distant_floor(
+                            player_start,
+                            &placed,
+                            &occupied,
+                            &mut rng,
+                            gamedata.max_dijkstra_depth,
+                        )
+                    };
+                    placed.push(exit_pos);
+                    map.set_tile(exit_pos, TileType::Exit);
+                    gamedata.spawn_stairs(&mut commands, exit_pos, gamedata.level_index(exit));
+                });
+
+            occupied.extend(placed.iter().copied());
+            gamedata.spawn_entities(
+                &mut commands,
+                &mut rng,
+                map_level_def,
+                &map,
+                &entity_spawns,
+                &spawn_limits,
+            );
+            gamedata.spawn_named_entities(&mut commands, &mut rng, &named_spawns);
 
-    let theme = map_level_def.get_theme(&gamedata);
-    commands.insert_resource(*theme);
-    commands.insert_resource(MapInfo {
-        name: map_level_def.name.clone(),
-    });
+            if let Some(boss) = &map_level_def.boss {
+                let boss_spawn = group_points(&map, amulet_start, 1, &mut occupied)
+                    .into_iter()
+                    .map(|pt| (pt, boss.clone()))
+                    .collect::<Vec<(Point, String)>>();
+                gamedata.spawn_named_entities(&mut commands, &mut rng, &boss_spawn);
+            }
+
+            let theme = *map_level_def.get_theme(&gamedata);
+            let map_info = MapInfo {
+                name: map_level_def.name.clone(),
+                depth: map_level_def.depth,
+            };
+            let wander_map = WanderMap::new(&map, &rooms);
+            (map, theme, map_info, wander_map, player_start)
+        }
+    };
+
+    let player_start = step_off_stairs(&map, arrival, &mut occupied);
+
+    commands.insert_resource(theme);
+    commands.insert_resource(map_info);
+    commands.insert_resource(wander_map);
 
     let mut camera = DCCamera::new(
         gamedata.tile_display_width(),
         gamedata.tile_display_height(),
-        gamedata.map_width,
-        gamedata.map_height,
+        map.width(),
+        map.height(),
     );
     camera.center_on_point(player_start);
     commands.insert_resource(camera);
 
-    // spawn stuff
-    if map_level >= gamedata.game_levels.len() - 1 {
-        gamedata.spawn_amulet_of_yala(&mut commands, amulet_start);
-    } else {
-        map.set_tile(amulet_start, TileType::Exit);
-    }
-
     // spawn a player if there isn't already one, otherwise move the player
     // to the new starting location
+    occupied.insert(player_start);
     if let Ok((_, mut player_pos, mut player_fov)) = player_opt.get_single_mut() {
         player_pos.0.x = player_start.x;
         player_pos.0.y = player_start.y;
         player_fov.is_dirty = true;
+
+        // allies arrive next to the player
+        let spots = group_points(&map, player_start, allies.iter().len(), &mut occupied);
+        allies
+            .iter_mut()
+            .zip(spots)
+            .for_each(|((ally, optional_fov), pt)| {
+                commands
+                    .entity(ally)
+                    .remove::<(Target, LastSeenPlayer)>()
+                    .insert((Position(pt), Post(pt)));
+                if let Some(mut fov) = optional_fov {
+                    fov.is_dirty = true;
+                }
+            });
     } else {
         gamedata.spawn_player(&mut commands, player_start);
+        if let Some(companion) = &gamedata.player_template.companion {
+            group_points(&map, player_start, 1, &mut occupied)
+                .into_iter()
+                .for_each(|pt| gamedata.spawn_ally(&mut commands, &mut game_rng.0, pt, companion));
+        }
     }
-    gamedata.spawn_entities(&mut commands, &mut rng, map_level, &entity_spawns);
 
     commands.insert_resource(map);
 }
@@ -0,0 +1,48 @@
+use crate::prelude::*;
+
+/// How far from where the player was last seen a monster searches
+const SEARCH_RADIUS: f32 = 4.0;
+
+pub fn investigate_system(
+    mut map: ResMut<Map>,
+    gamedata: Res<GameData>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    mut movers: Query<(Entity, &Position, &Investigating, &mut LastSeenPlayer)>,
+) {
+    let mut maps = TargetMaps::default();
+
+    movers.for_each_mut(|(entity, pos, investigating, mut memory)| {
+        // head for where the player was last seen, and once there search around
+        if memory.turns_searching == 0 && pos.0 != memory.position {
+            let dijkstra_map = maps.get(&mut map, memory.position, gamedata.max_dijkstra_depth);
+            match dijkstra_map.find_lowest_exit(map.point_to_index(pos.0), &map) {
+                Some(destination) => {
+                    commands.entity(entity).insert(WantsToMove {
+                        destination: map.index_to_point(destination),
+                    });
+                    return;
+                }
+                None => memory.turns_searching = 1,
+            }
+        }
+
+        memory.turns_searching += 1;
+        if memory.turns_searching > investigating.search_turns {
+            commands.entity(entity).remove::<LastSeenPlayer>();
+            return;
+        }
+
+        let exits = map
+            .get_available_exits(map.point_to_index(pos.0))
+            .iter()
+            .map(|(idx, _)| map.index_to_point(*idx))
+            .filter(|pt| pt.pythagorean_distance(memory.position) <= SEARCH_RADIUS)
+            .collect::<Vec<Point>>();
+        if let Some(destination) = rng.0.random_slice_entry(&exits) {
+            commands.entity(entity).insert(WantsToMove {
+                destination: *destination,
+            });
+        }
+    });
+}
@@ -0,0 +1,37 @@
+use crate::prelude::*;
+
+pub fn keep_distance_system(
+    mut map: ResMut<Map>,
+    gamedata: Res<GameData>,
+    mut commands: Commands,
+    movers: Query<(Entity, &Position, &KeepingDistance, &Target)>,
+    positions: Query<&Position>,
+) {
+    let mut maps = TargetMaps::default();
+    movers
+        .iter()
+        .for_each(|(entity, pos, keep_distance, target)| {
+            let Ok(target_pos) = positions.get(target.0) else {
+                return;
+            };
+            let dijkstra_map = maps.get(&mut map, target_pos.0, gamedata.max_monster_visibility);
+            let idx = map.point_to_index(pos.0);
+            let distance = pos.0.pythagorean_distance(target_pos.0);
+
+            let destination = if distance < keep_distance.0 - 0.5 {
+                dijkstra_map.find_highest_exit(idx, map.as_ref())
+            } else if distance > keep_distance.0 + 1.5 {
+                dijkstra_map
+                    .find_lowest_exit(idx, map.as_ref())
+                    .filter(|exit| map.index_to_point(*exit) != target_pos.0)
+            } else {
+                None
+            };
+
+            if let Some(destination) = destination {
+                commands.entity(entity).insert(WantsToMove {
+                    destination: map.index_to_point(destination),
+                });
+            }
+        });
+}
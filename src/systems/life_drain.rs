@@ -0,0 +1,10 @@
+use crate::prelude::*;
+
+pub fn life_drain_system(hits: Query<&Hit>, mut drainers: Query<(&DrainsLife, &mut Health)>) {
+    hits.iter().filter(|hit| hit.amount > 0).for_each(|hit| {
+        if let Ok((drain, mut health)) = drainers.get_mut(hit.attacker) {
+            let healing = (hit.amount as f32 * drain.0).round() as i32;
+            health.current = i32::min(health.max, health.current + healing);
+        }
+    });
+}
@@ -1,13 +1,28 @@
+use std::collections::HashSet;
+
 use crate::prelude::*;
 
 pub fn movement_system(
-    mut want_move_query: Query<(Entity, &WantsToMove, Option<&mut FieldOfView>)>,
+    mut want_move_query: Query<(
+        Entity,
+        &WantsToMove,
+        Option<&mut FieldOfView>,
+        Option<&Stealth>,
+    )>,
     map: Res<Map>,
-
+    gamedata: Res<GameData>,
+    blockers: Query<&Position, With<Health>>,
+    weapons: Query<(&Carried, &Weapon, &StealthBonus)>,
     mut commands: Commands,
 ) {
-    for (entity, want_move, optional_fov) in want_move_query.iter_mut() {
-        if map.can_enter_tile(want_move.destination) {
+    // positions only change once the commands are applied, so remember where
+    // movers are going to stop two of them ending up on the same tile
+    let mut claimed = HashSet::new();
+    for (entity, want_move, optional_fov, optional_stealth) in want_move_query.iter_mut() {
+        let blocked = blockers.iter().any(|pos| pos.0 == want_move.destination)
+            || claimed.contains(&want_move.destination);
+        if map.can_enter_tile(want_move.destination) && !blocked {
+            claimed.insert(want_move.destination);
             commands
                 .entity(entity)
                 .insert(Position(want_move.destination));
@@ -15,6 +30,18 @@ pub fn movement_system(
             if let Some(mut fov) = optional_fov {
                 fov.is_dirty = true;
             }
+
+            // only the player's footsteps are worth listening for
+            if let Some(stealth) = optional_stealth {
+                let stealth = stealth.0 + equipped_stealth(&weapons, entity);
+                let loudness = gamedata.noise.moving - stealth as f32;
+                if loudness > 0.0 {
+                    commands.spawn(Noise {
+                        origin: want_move.destination,
+                        loudness,
+                    });
+                }
+            }
         }
         commands.entity(entity).remove::<WantsToMove>();
     }
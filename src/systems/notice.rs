@@ -0,0 +1,27 @@
+use crate::prelude::*;
+
+/// What a sleeping monster's roll has to beat, on top of the player's stealth
+const NOTICE_DIFFICULTY: i32 = 15;
+
+/// Sleeping monsters that can see the player may wake up, each turn rolling
+/// a d20 plus their perception against the player's stealth
+pub fn notice_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    player: Query<(Entity, &Position, &Stealth), With<Player>>,
+    sleepers: Query<(Entity, &FieldOfView, &Perception), With<Asleep>>,
+    weapons: Query<(&Carried, &Weapon, &StealthBonus)>,
+) {
+    if let Ok((player, player_pos, stealth)) = player.get_single() {
+        let stealth = stealth.0 + equipped_stealth(&weapons, player);
+
+        sleepers
+            .iter()
+            .filter(|(_, fov, _)| fov.visible_tiles.contains(&player_pos.0))
+            .for_each(|(entity, _, perception)| {
+                if rng.0.gen_range(1..=20) + perception.0 > NOTICE_DIFFICULTY + stealth {
+                    commands.entity(entity).remove::<Asleep>();
+                }
+            });
+    }
+}
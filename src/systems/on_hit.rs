@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+/// Whoever is hit by a weapon with status effects suffers them. Damage from
+/// the effects themselves doesn't count, or a burn would never go out
+pub fn on_hit_system(
+    mut commands: Commands,
+    hits: Query<&Hit>,
+    weapons: Query<(&Carried, &Weapon, &OnHit)>,
+    mut afflicted: Query<&mut Afflictions>,
+) {
+    let mut new_afflictions: HashMap<Entity, Vec<Affliction>> = HashMap::new();
+    hits.iter().filter(|hit| hit.melee).for_each(|hit| {
+        weapons
+            .iter()
+            .filter(|(carried, weapon, _)| carried.0 == hit.attacker && weapon.equipped)
+            .flat_map(|(_, _, on_hit)| on_hit.0.iter())
+            .for_each(|effect| {
+                let affliction = Affliction {
+                    effect: *effect,
+                    source: hit.attacker,
+                    turns_left: effect.turns(),
+                };
+                if let Ok(mut afflictions) = afflicted.get_mut(hit.target) {
+                    afflictions.0.push(affliction);
+                } else {
+                    new_afflictions
+                        .entry(hit.target)
+                        .or_default()
+                        .push(affliction);
+                }
+            });
+    });
+
+    new_afflictions
+        .into_iter()
+        .for_each(|(target, afflictions)| {
+            if let Some(mut entity) = commands.get_entity(target) {
+                entity.insert(Afflictions(afflictions));
+            }
+        });
+}
@@ -0,0 +1,51 @@
+use crate::prelude::*;
+
+const PATROL_WAYPOINTS: usize = 3;
+
+pub fn patrol_system(
+    map: Res<Map>,
+    gamedata: Res<GameData>,
+    mut wander_map: ResMut<WanderMap>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    mut movers: Query<(Entity, &Position, Option<&mut PatrolRoute>), With<Patrolling>>,
+) {
+    movers.for_each_mut(|(entity, pos, route)| match route {
+        Some(mut route) => {
+            if pos.0 == route.waypoints[route.next] {
+                route.next = (route.next + 1) % route.waypoints.len();
+                if pos.0 == route.waypoints[route.next] {
+                    return;
+                }
+            }
+            match wander_map.step_towards(
+                &map,
+                route.waypoints[route.next],
+                pos.0,
+                gamedata.max_dijkstra_depth,
+            ) {
+                Some(destination) => {
+                    commands.entity(entity).insert(WantsToMove { destination });
+                }
+                None => route.next = (route.next + 1) % route.waypoints.len(),
+            }
+        }
+        None => {
+            let floor = map
+                .tiles
+                .iter()
+                .enumerate()
+                .filter(|(_, tile)| **tile == TileType::Floor)
+                .map(|(idx, _)| map.index_to_point(idx))
+                .collect::<Vec<Point>>();
+
+            let mut waypoints = vec![pos.0];
+            (0..PATROL_WAYPOINTS).for_each(|_| waypoints.extend(rng.0.random_slice_entry(&floor)));
+            // without any floor to go to, the route is just where it stands
+            let next = 1 % waypoints.len();
+            commands
+                .entity(entity)
+                .insert(PatrolRoute { waypoints, next });
+        }
+    });
+}
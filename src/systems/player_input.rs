@@ -5,14 +5,68 @@ use crate::prelude::*;
 pub fn player_input_system(
     mut commands: Commands,
     key: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    mouse_pos: Res<Position>,
+    camera: Res<DCCamera>,
+    map: Res<Map>,
+    gamedata: Res<GameData>,
     mut next_state: ResMut<NextState<TurnState>>,
-    players: Query<(Entity, &Position), (With<Player>, Without<Item>, Without<Enemy>)>,
+    mut players: Query<
+        (Entity, &Position, &Faction, &mut Purse),
+        (With<Player>, Without<Item>, Without<Enemy>),
+    >,
     items: Query<
         (Entity, Option<&Position>, &DCName, Option<&Carried>),
-        (With<Item>, Without<Player>, Without<Enemy>),
+        (With<Item>, Without<Player>, Without<Enemy>, Without<Gold>),
+    >,
+    gold: Query<(Entity, &Position, &Gold)>,
+    enemies: Query<
+        (Entity, &Position, &Faction, Option<&Ally>, Option<&Shop>),
+        (With<Enemy>, Without<Player>, Without<Item>),
     >,
-    enemies: Query<(Entity, &Position), (With<Enemy>, Without<Player>, Without<Item>)>,
 ) {
+    if key.pressed(KeyCode::Tab) {
+        next_state.set(TurnState::DungeonOverview);
+        return;
+    }
+
+    if key.any_pressed([KeyCode::R, KeyCode::X]) {
+        let kind = if key.pressed(KeyCode::R) {
+            AutoActionKind::Rest
+        } else {
+            AutoActionKind::Explore
+        };
+        let (player, _, _, _) = players.single();
+        commands.entity(player).insert(AutoAction::new(kind));
+        return;
+    }
+
+    // travel to a clicked tile the player knows about, or the closest known exit
+    let travel_target = if mouse.pressed(MouseButton::Left) {
+        let target = camera.screen_point_to_world_point(mouse_pos.0);
+        (map.in_bounds(target)
+            && map.can_enter_tile(target)
+            && map.revealed[map.point_to_index(target)] != Revealed::NotSeen)
+            .then_some(target)
+    } else if key.pressed(KeyCode::E) {
+        let (_, player_pos, _, _) = players.single();
+        (0..map.tiles.len())
+            .filter(|idx| {
+                map.tiles[*idx] == TileType::Exit && map.revealed[*idx] != Revealed::NotSeen
+            })
+            .map(|idx| map.index_to_point(idx))
+            .min_by_key(|pt| pt.pythagorean_squared_distance(player_pos.0))
+    } else {
+        None
+    };
+    if let Some(target) = travel_target {
+        let (player, _, _, _) = players.single();
+        commands
+            .entity(player)
+            .insert(AutoAction::new(AutoActionKind::Travel(target)));
+        return;
+    }
+
     if key.any_pressed([
         KeyCode::Left,
         KeyCode::Right,
@@ -30,7 +84,7 @@ pub fn player_input_system(
         KeyCode::Key8,
         KeyCode::Key9,
     ]) {
-        let (player, player_pos) = players.single();
+        let (player, player_pos, player_faction, mut purse) = players.single_mut();
         let delta = if key.pressed(KeyCode::Left) {
             Point::new(-1, 0)
         } else if key.pressed(KeyCode::Right) {
@@ -47,6 +101,12 @@ pub fn player_input_system(
                     commands.entity(item_entity).remove::<Position>();
                     commands.entity(item_entity).insert(Carried(player));
                 });
+            gold.iter()
+                .filter(|(_, pos, _)| pos.0 == player_pos.0)
+                .for_each(|(pile, _, amount)| {
+                    purse.0 += amount.0;
+                    commands.entity(pile).despawn();
+                });
 
             Point::zero()
         } else if key.pressed(KeyCode::Key1) {
@@ -73,12 +133,31 @@ pub fn player_input_system(
 
         let destination = player_pos.0 + delta;
         if delta.x != 0 || delta.y != 0 {
+            // holding shift attacks whoever is there, even shopkeepers
+            let force_attack = key.pressed(KeyCode::LShift);
             let mut hit_something = false;
+            let mut shop_opened = false;
             enemies
                 .iter()
-                .filter(|(_, pos)| pos.0 == destination)
-                .for_each(|(enemy, _)| {
+                .filter(|(_, pos, _, _, _)| pos.0 == destination)
+                .for_each(|(enemy, _, faction, ally, shop)| {
+                    // allies swap places with the player instead
+                    if ally.is_some() {
+                        commands.entity(enemy).insert(Position(player_pos.0));
+                        return;
+                    }
                     hit_something = true;
+                    if shop.is_some()
+                        && !force_attack
+                        && !gamedata.is_hostile(&faction.0, &player_faction.0)
+                    {
+                        commands.insert_resource(ShopScreen {
+                            shop: enemy,
+                            selling: false,
+                        });
+                        shop_opened = true;
+                        return;
+                    }
 
                     commands.spawn((
                         (),
@@ -92,6 +171,10 @@ pub fn player_input_system(
             if !hit_something {
                 commands.entity(player).insert(WantsToMove { destination });
             }
+            if shop_opened {
+                next_state.set(TurnState::Shopping);
+                return;
+            }
         }
 
         next_state.set(TurnState::PlayerTurn);
@@ -104,7 +187,7 @@ fn use_item(
     player: Entity,
     items: Query<
         (Entity, Option<&Position>, &DCName, Option<&Carried>),
-        (With<Item>, Without<Player>, Without<Enemy>),
+        (With<Item>, Without<Player>, Without<Enemy>, Without<Gold>),
     >,
 ) -> Point {
     let mut item_number = 1;
@@ -0,0 +1,33 @@
+use crate::prelude::*;
+
+/// How far away members of the same faction notice one of them being attacked
+const PROVOKE_RADIUS: f32 = 10.0;
+
+pub fn provoke_system(
+    gamedata: Res<GameData>,
+    hits: Query<&Hit>,
+    mut factions: Query<(&mut Faction, Option<&Position>)>,
+) {
+    hits.iter().for_each(|hit| {
+        let attacked_by_player = factions
+            .get(hit.attacker)
+            .is_ok_and(|(faction, _)| faction.0 == gamedata.player_template.faction);
+        let (Some(name), Some(center)) = (&hit.target_faction, hit.target_position) else {
+            return;
+        };
+        if !attacked_by_player {
+            return;
+        }
+        let Some(provoked) = &gamedata.faction(name).provoked else {
+            return;
+        };
+
+        factions
+            .iter_mut()
+            .filter(|(faction, pos)| {
+                faction.0 == *name
+                    && pos.is_some_and(|pos| center.pythagorean_distance(pos.0) <= PROVOKE_RADIUS)
+            })
+            .for_each(|(mut faction, _)| faction.0 = provoked.clone());
+    });
+}
@@ -0,0 +1,15 @@
+use crate::prelude::*;
+
+/// Heals whoever regenerates, as long as they aren't starving
+pub fn regeneration_system(mut healing: Query<(&mut Regeneration, &mut Health, Option<&Hunger>)>) {
+    healing.for_each_mut(|(mut regeneration, mut health, hunger)| {
+        if hunger.is_some_and(|h| h.food == 0) {
+            return;
+        }
+        regeneration.turns += 1;
+        if regeneration.turns >= regeneration.every {
+            regeneration.turns = 0;
+            health.current = i32::min(health.max, health.current + 1);
+        }
+    });
+}
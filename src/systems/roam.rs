@@ -0,0 +1,31 @@
+use crate::prelude::*;
+
+pub fn roam_system(
+    map: Res<Map>,
+    gamedata: Res<GameData>,
+    mut wander_map: ResMut<WanderMap>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    movers: Query<(Entity, &Position, Option<&RoamTarget>), With<Roaming>>,
+) {
+    if wander_map.waypoints.is_empty() {
+        return;
+    }
+
+    movers.iter().for_each(|(entity, pos, target)| {
+        match wander_map.roam(
+            &map,
+            target.map(|target| target.0),
+            pos.0,
+            &mut rng.0,
+            gamedata.max_dijkstra_depth,
+        ) {
+            RoamStep::Move(destination) => {
+                commands.entity(entity).insert(WantsToMove { destination });
+            }
+            RoamStep::Retarget(next) => {
+                commands.entity(entity).insert(RoamTarget(next));
+            }
+        }
+    });
+}
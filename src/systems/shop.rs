@@ -0,0 +1,135 @@
+use crate::prelude::*;
+
+const LETTER_KEYS: [KeyCode; 26] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+];
+
+/// Shopkeepers buy items back for their price divided by this
+const SELL_FACTOR: i32 = 2;
+
+pub fn shop_system(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<TurnState>>,
+    key_press: Res<Input<KeyCode>>,
+    knowledge: Res<ItemKnowledge>,
+    mut screen: ResMut<ShopScreen>,
+    shops: Query<&DCName, With<Shop>>,
+    mut player: Query<(Entity, &mut Purse), With<Player>>,
+    items: Query<(Entity, &DCName, &Carried, &Price, Option<&Weapon>), With<Item>>,
+) {
+    let (player, mut purse) = player.single_mut();
+    let Ok(shopkeeper) = shops.get(screen.shop) else {
+        next_state.set(TurnState::AwaitingInput);
+        return;
+    };
+
+    let chosen = LETTER_KEYS.iter().position(|k| key_press.pressed(*k));
+
+    // (name, price, item) for whoever is selling. Equipped weapons aren't for sale
+    let seller = if screen.selling { player } else { screen.shop };
+    let entries = items
+        .iter()
+        .filter(|(_, _, carried, _, weapon)| {
+            carried.0 == seller && !weapon.is_some_and(|w| w.equipped)
+        })
+        .map(|(item, name, _, price, _)| {
+            let price = if screen.selling {
+                price.0 / SELL_FACTOR
+            } else {
+                price.0
+            };
+            (name.0.clone(), price, item)
+        })
+        .collect::<Vec<(String, i32, Entity)>>();
+
+    if let Some((_, price, item)) = chosen.and_then(|idx| entries.get(idx)) {
+        if screen.selling {
+            purse.0 += price;
+            commands.entity(*item).despawn();
+        } else if purse.0 >= *price {
+            purse.0 -= price;
+            commands.entity(*item).insert(Carried(player));
+        }
+    }
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(2);
+    let title = if screen.selling {
+        format!("Selling to {}", shopkeeper.0)
+    } else {
+        format!("Buying from {}", shopkeeper.0)
+    };
+    draw_batch.print_color_centered(2, title, ColorPair::new(YELLOW, BLACK));
+    draw_batch.print_color_centered(
+        3,
+        format!("You have {} gold", purse.0),
+        ColorPair::new(GOLD, BLACK),
+    );
+
+    let mut y = 5;
+    if entries.is_empty() {
+        draw_batch.print_color_centered(y, "Nothing to trade.", ColorPair::new(GREY, BLACK));
+        y += 1;
+    }
+    entries
+        .iter()
+        .take(LETTER_KEYS.len())
+        .enumerate()
+        .for_each(|(idx, (name, price, _))| {
+            let color = if screen.selling || purse.0 >= *price {
+                ColorPair::new(WHITE, BLACK)
+            } else {
+                ColorPair::new(GREY, BLACK)
+            };
+            draw_batch.print_color(
+                Point::new(6, y).to_bracket_point(),
+                format!(
+                    "{}) {:<30} {:>4} gold",
+                    (b'a' + idx as u8) as char,
+                    knowledge.display_name(name),
+                    price
+                ),
+                color,
+            );
+            y += 1;
+        });
+
+    draw_batch.print_color_centered(
+        y + 1,
+        "Press a letter to trade, Tab to switch between buying and selling, Escape to leave.",
+        ColorPair::new(GREEN, BLACK),
+    );
+    draw_batch.submit(10000).expect("Batch error");
+
+    if key_press.pressed(KeyCode::Tab) {
+        screen.selling = !screen.selling;
+    } else if key_press.pressed(KeyCode::Escape) {
+        commands.remove_resource::<ShopScreen>();
+        next_state.set(TurnState::AwaitingInput);
+    }
+}
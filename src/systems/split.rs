@@ -0,0 +1,37 @@
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+/// Monsters that split on hit share their remaining health with a copy of
+/// themselves. This is the last system to look at the turn's hits
+pub fn split_system(
+    mut commands: Commands,
+    gamedata: Res<GameData>,
+    mut rng: ResMut<GameRng>,
+    map: Res<Map>,
+    hits: Query<(Entity, &Hit)>,
+    mut splitters: Query<(&mut Health, &Position, &DCName), With<SplitsOnHit>>,
+    positions: Query<&Position>,
+) {
+    let mut occupied = positions.iter().map(|p| p.0).collect::<HashSet<Point>>();
+
+    hits.iter().for_each(|(message, hit)| {
+        commands.entity(message).despawn();
+
+        let Ok((mut health, pos, name)) = splitters.get_mut(hit.target) else {
+            return;
+        };
+        if health.current < 2 {
+            return;
+        }
+        if let Some(pt) = group_points(&map, pos.0, 1, &mut occupied).pop() {
+            let half = health.current / 2;
+            health.current -= half;
+            let copy = gamedata.spawn_summoned(&mut commands, &mut rng.0, pt, &name.0);
+            commands.entity(copy).insert(Health {
+                current: half,
+                max: health.max,
+            });
+        }
+    });
+}
@@ -0,0 +1,42 @@
+use crate::prelude::*;
+
+/// Thieves next to the player take one of their items, then stop stealing
+/// and switch to their getaway behaviour
+pub fn steal_system(
+    mut commands: Commands,
+    gamedata: Res<GameData>,
+    mut rng: ResMut<GameRng>,
+    mut thieves: Query<(Entity, &Position, &Stealing, &Target, &mut Abilities)>,
+    player: Query<(Entity, &Position), With<Player>>,
+    mut items: Query<(Entity, &Carried, Option<&mut Weapon>), With<Item>>,
+) {
+    let Ok((player, player_pos)) = player.get_single() else {
+        return;
+    };
+
+    thieves.for_each_mut(|(entity, pos, stealing, target, mut abilities)| {
+        if target.0 != player || !within_steal_reach(pos.0, player_pos.0) {
+            return;
+        }
+        let carried = items
+            .iter()
+            .filter(|(_, carried, _)| carried.0 == player)
+            .map(|(item, _, _)| item)
+            .collect::<Vec<Entity>>();
+        let Some(item) = rng.0.random_slice_entry(&carried).copied() else {
+            return;
+        };
+
+        if let Ok((_, _, Some(mut weapon))) = items.get_mut(item) {
+            weapon.equipped = false;
+        }
+        commands.entity(item).insert(Carried(entity));
+
+        abilities
+            .0
+            .retain(|a| !matches!(a.template.ability, Ability::Steal { .. }));
+        commands.entity(entity).insert(Behaviour(
+            gamedata.behaviour_named(&stealing.behaviour).tree.clone(),
+        ));
+    });
+}
@@ -0,0 +1,22 @@
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+pub fn summon_system(
+    mut commands: Commands,
+    gamedata: Res<GameData>,
+    mut rng: ResMut<GameRng>,
+    map: Res<Map>,
+    summoners: Query<(&Position, &Summoning)>,
+    positions: Query<&Position>,
+) {
+    let mut occupied = positions.iter().map(|p| p.0).collect::<HashSet<Point>>();
+
+    summoners.iter().for_each(|(pos, summoning)| {
+        group_points(&map, pos.0, summoning.count, &mut occupied)
+            .into_iter()
+            .for_each(|pt| {
+                gamedata.spawn_summoned(&mut commands, &mut rng.0, pt, &summoning.template);
+            });
+    });
+}
@@ -4,8 +4,17 @@ pub fn tooltips_system(
     mouse_pos: Res<Position>,
     camera: Res<DCCamera>,
     gamedata: Res<GameData>,
+    knowledge: Res<ItemKnowledge>,
     fov: Query<&FieldOfView, With<Player>>,
-    positions: Query<(&Position, &DCName, Option<&Health>)>,
+    positions: Query<(
+        &Position,
+        &DCName,
+        Option<&Health>,
+        Option<&Asleep>,
+        Option<&Render>,
+        Option<&Afflictions>,
+        Option<&RarityName>,
+    )>,
 ) {
     let player_fov = fov.single();
 
@@ -13,17 +22,31 @@ pub fn tooltips_system(
 
     let mut draw_batch = DrawBatch::new();
     draw_batch.target(2);
-    for (_, name, option_health) in positions
-        .iter()
-        .filter(|(pos, _, _)| player_fov.visible_tiles.contains(&pos.0) && pos.0 == world_mouse_pos)
+    for (_, name, option_health, option_asleep, option_render, option_afflictions, option_rarity) in
+        positions.iter().filter(|(pos, _, _, _, _, _, _)| {
+            player_fov.visible_tiles.contains(&pos.0) && pos.0 == world_mouse_pos
+        })
     {
         let screen_pos = mouse_pos.0 * gamedata.tile_display_width();
-        let display = if let Some(health) = option_health {
-            format!("{} : {} hp", &name.0, health.current)
+        let name = knowledge.display_name(&name.0);
+        let mut display = if let Some(health) = option_health {
+            format!("{} : {} hp", name, health.current)
         } else {
-            name.0.clone()
+            name
         };
-        draw_batch.print(screen_pos.to_bracket_point(), &display);
+        if let Some(rarity) = option_rarity {
+            display.push_str(&format!(" ({})", rarity.0));
+        }
+        if option_asleep.is_some() {
+            display.push_str(" (asleep)");
+        }
+        if let Some(afflictions) = option_afflictions {
+            afflictions.0.iter().for_each(|a| {
+                display.push_str(&format!(" ({})", a.effect.name()));
+            });
+        }
+        let color = option_render.map_or(ColorPair::new(WHITE, BLACK), |r| r.color);
+        draw_batch.print_color(screen_pos.to_bracket_point(), &display, color);
     }
 
     draw_batch.submit(10100).expect("Batch error");
@@ -3,40 +3,113 @@ use crate::prelude::*;
 pub fn use_items_system(
     mut commands: Commands,
     mut map: ResMut<Map>,
+    gamedata: Res<GameData>,
+    mut rng: ResMut<GameRng>,
+    mut knowledge: ResMut<ItemKnowledge>,
     activations: Query<(Entity, &ActivateItem)>,
     items: Query<
         (
             Entity,
             Option<&ProvidesHealing>,
             Option<&ProvidesDungeonMap>,
+            Option<&ProvidesCharm>,
+            Option<&ProvidesSummon>,
+            Option<&ProvidesIdentify>,
+            Option<&ProvidesFood>,
         ),
         Without<Weapon>,
     >,
     mut weapons: Query<(Entity, &Carried, &mut Weapon)>,
     mut healed: Query<(Entity, &mut Health)>,
+    mut eaters: Query<&mut Hunger>,
+    positions: Query<&Position>,
+    users: Query<(&Position, &FieldOfView)>,
+    charmable: Query<(Entity, &Position), (With<Enemy>, Without<Ally>, Without<Boss>)>,
+    player: Query<Entity, With<Player>>,
+    names: Query<(&DCName, Option<&Carried>), With<Item>>,
 ) {
+    let player = player.single();
     activations
         .iter()
         .for_each(|(activate_entity, activation)| {
             let mut is_weapon = false;
             items
                 .iter()
-                .filter(|(item_entity, _, _)| *item_entity == activation.item)
-                .for_each(|(_, optional_healing, optional_map)| {
-                    if let Some(healing) = optional_healing {
-                        for (_, mut health) in
-                            healed.iter_mut().filter(|h| h.0 == activation.used_by)
+                .filter(|(item_entity, _, _, _, _, _, _)| *item_entity == activation.item)
+                .for_each(
+                    |(
+                        _,
+                        optional_healing,
+                        optional_map,
+                        optional_charm,
+                        optional_summon,
+                        optional_identify,
+                        optional_food,
+                    )| {
+                        if let Some(healing) = optional_healing {
+                            for (_, mut health) in
+                                healed.iter_mut().filter(|h| h.0 == activation.used_by)
+                            {
+                                health.current =
+                                    i32::min(health.max, health.current + healing.amount);
+                            }
+                        }
+                        if let (Some(food), Ok(mut hunger)) =
+                            (optional_food, eaters.get_mut(activation.used_by))
                         {
-                            health.current = i32::min(health.max, health.current + healing.amount);
+                            hunger.food = i32::min(hunger.max, hunger.food + food.amount);
                         }
-                    }
-                    if optional_map.is_some() {
-                        map.revealed
-                            .iter_mut()
-                            .filter(|t| **t == Revealed::NotSeen)
-                            .for_each(|t| *t = Revealed::Mapped);
-                    }
-                });
+                        if optional_map.is_some() {
+                            map.revealed
+                                .iter_mut()
+                                .filter(|t| **t == Revealed::NotSeen)
+                                .for_each(|t| *t = Revealed::Mapped);
+                        }
+                        if let (Some(charm), Ok((user_pos, fov))) =
+                            (optional_charm, users.get(activation.used_by))
+                        {
+                            let closest = charmable
+                                .iter()
+                                .filter(|(_, pos)| {
+                                    fov.visible_tiles.contains(&pos.0)
+                                        && pos.0.pythagorean_distance(user_pos.0)
+                                            <= charm.range as f32
+                                })
+                                .min_by_key(|(_, pos)| {
+                                    pos.0.pythagorean_squared_distance(user_pos.0)
+                                });
+                            if let Some((monster, _)) = closest {
+                                gamedata.make_ally(&mut commands.entity(monster));
+                            }
+                        }
+                        if optional_identify.is_some() && activation.used_by == player {
+                            names
+                                .iter()
+                                .filter(|(_, carried)| {
+                                    carried.is_some_and(|c| c.0 == activation.used_by)
+                                })
+                                .for_each(|(name, _)| {
+                                    knowledge.identify(&name.0);
+                                });
+                        }
+                        if let (Some(summon), Ok(user_pos)) =
+                            (optional_summon, positions.get(activation.used_by))
+                        {
+                            let mut occupied = positions.iter().map(|pos| pos.0).collect();
+                            group_points(&map, user_pos.0, summon.count, &mut occupied)
+                                .into_iter()
+                                .for_each(|pt| {
+                                    let ally = gamedata.spawn_summoned(
+                                        &mut commands,
+                                        &mut rng.0,
+                                        pt,
+                                        &summon.template,
+                                    );
+                                    gamedata.make_ally(&mut commands.entity(ally));
+                                });
+                        }
+                    },
+                );
 
             weapons
                 .iter_mut()
@@ -46,6 +119,20 @@ pub fn use_items_system(
                     is_weapon = true;
                 });
 
+            // using an item is the surest way to find out what it is
+            if activation.used_by == player {
+                if let Ok((name, _)) = names.get(activation.item) {
+                    knowledge.identify(&name.0);
+                }
+            }
+
+            if let Ok(pos) = positions.get(activation.used_by) {
+                commands.spawn(Noise {
+                    origin: pos.0,
+                    loudness: gamedata.noise.using_items,
+                });
+            }
+
             if is_weapon {
                 weapons
                     .iter_mut()
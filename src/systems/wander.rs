@@ -0,0 +1,17 @@
+use crate::prelude::*;
+
+pub fn wander_system(
+    map: Res<Map>,
+    mut rng: ResMut<GameRng>,
+    mut commands: Commands,
+    movers: Query<(Entity, &Position), With<Wandering>>,
+) {
+    movers.iter().for_each(|(entity, pos)| {
+        let exits = map.get_available_exits(map.point_to_index(pos.0));
+        if let Some((destination, _)) = rng.0.random_slice_entry(&exits) {
+            commands.entity(entity).insert(WantsToMove {
+                destination: map.index_to_point(*destination),
+            });
+        }
+    });
+}
@@ -4,11 +4,13 @@ use crate::prelude::*;
 pub enum TurnState {
     #[default]
     InitGame,
-    NextLevel,
+    ChangeLevel,
     InitLevel,
     AwaitingInput,
     PlayerTurn,
     MonsterTurn,
+    DungeonOverview,
+    Shopping,
     GameOver,
     Victory,
 }
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// What a roaming monster does this turn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoamStep {
+    Move(Point),
+    /// heads for a different waypoint from the next turn on
+    Retarget(usize),
+}
+
+/// Dijkstra maps towards the middle of every room, shared by all the monsters
+/// roaming from room to room, and towards the other fixed spots monsters head
+/// for, like their posts. Each map is only built once somebody needs it
+#[derive(Default, Resource)]
+pub struct WanderMap {
+    pub waypoints: Vec<Point>,
+    maps: HashMap<Point, Arc<DijkstraMap>>,
+}
+impl WanderMap {
+    pub fn new(map: &Map, rooms: &[Room]) -> Self {
+        let waypoints = rooms
+            .iter()
+            .filter(|room| !room.is_corridor && !room.tiles.is_empty())
+            .filter_map(|room| {
+                let count = room.tiles.len() as i32;
+                let sum = room.tiles.iter().fold(Point::zero(), |sum, pt| sum + *pt);
+                let middle = Point::new(sum.x / count, sum.y / count);
+                room.tiles
+                    .iter()
+                    .filter(|pt| map.can_enter_tile(**pt))
+                    .min_by_key(|pt| pt.pythagorean_squared_distance(middle))
+                    .copied()
+            })
+            .collect();
+
+        Self {
+            waypoints,
+            maps: HashMap::new(),
+        }
+    }
+
+    /// What a monster roaming from room to room does next, on its way from
+    /// `from` to waypoint number `target`. On arriving, or when the way is
+    /// blocked, it heads for another room instead
+    pub fn roam(
+        &mut self,
+        map: &Map,
+        target: Option<usize>,
+        from: Point,
+        rng: &mut StdRng,
+        max_depth: f32,
+    ) -> RoamStep {
+        let step = target
+            .filter(|target| self.waypoints[*target] != from)
+            .and_then(|target| self.step_towards(map, self.waypoints[target], from, max_depth));
+        match step {
+            Some(destination) => RoamStep::Move(destination),
+            None => {
+                // never pick the waypoint that was just reached or given up on
+                let others = self.waypoints.len() - usize::from(target.is_some());
+                let mut next = rng.gen_range(0..others.max(1));
+                if target.is_some_and(|target| next >= target) && others > 0 {
+                    next += 1;
+                }
+                RoamStep::Retarget(next)
+            }
+        }
+    }
+
+    /// The next step from `from` towards `target`, which shouldn't move
+    pub fn step_towards(
+        &mut self,
+        map: &Map,
+        target: Point,
+        from: Point,
+        max_depth: f32,
+    ) -> Option<Point> {
+        let dijkstra_map = self.maps.entry(target).or_insert_with(|| {
+            Arc::new(DijkstraMap::new(
+                map.width(),
+                map.height(),
+                &[map.point_to_index(target)],
+                map,
+                max_depth,
+            ))
+        });
+        dijkstra_map
+            .find_lowest_exit(map.point_to_index(from), map)
+            .map(|idx| map.index_to_point(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_roam_moves_on() {
+        let mut map = Map::new(7, 3, TileType::Wall);
+        map.clear_rect(IRect::with_size(1, 1, 5, 1));
+        let mut wander_map = WanderMap {
+            waypoints: vec![Point::new(1, 1), Point::new(5, 1)],
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut roam = |target, from| wander_map.roam(&map, target, from, &mut rng, 100.0);
+
+        assert_eq!(
+            roam(Some(0), Point::new(3, 1)),
+            RoamStep::Move(Point::new(2, 1))
+        );
+        // arriving at the first waypoint sends it on to the other
+        assert_eq!(roam(Some(0), Point::new(1, 1)), RoamStep::Retarget(1));
+        assert_eq!(
+            roam(Some(1), Point::new(1, 1)),
+            RoamStep::Move(Point::new(2, 1))
+        );
+        assert_eq!(roam(Some(1), Point::new(5, 1)), RoamStep::Retarget(0));
+    }
+}